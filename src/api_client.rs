@@ -1,135 +1,1828 @@
-use anyhow::anyhow;
-use reqwest::Client;
 use crate::cache::{Cache, CachedResponse};
-use crate::models::{CodeSearchResponse, RateLimit, SearchResponse};
+use crate::errors::Error;
+use crate::http_backend::{HttpBackend, ReqwestBackend};
+use crate::search_query::{CodeSearchQuery, GithubSearchQuery, normalize_query, validate_query};
+use crate::models::{
+    CodeSearchFile, CodeSearchResponse, CombinedResult, CommitSearchResponse, IssueSearchResponse, LenientSearchResponse,
+    MinimalSearchResponse, Paginated, RateLimit, RateLimitInfo, Repo, RepositoryDetails,
+    ResumeToken, SearchResponse, TopicSearchResponse,
+};
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use tracing::{debug, warn};
+use reqwest::Client;
+
+// GitHub hard-caps every search at 1000 results (e.g. ten pages of 100);
+// requesting past it yields a 422, so pagination must stop at this boundary
+const SEARCH_RESULT_CAP: u32 = 1000;
+
+// Where requests go unless a custom base URL is configured
+const DEFAULT_BASE_URL: &str = "https://api.github.com";
+
+// Wraps a reqwest `Client` together with the API base URL, so the same
+// code can talk to github.com or a GitHub Enterprise Server instance.
+pub struct GithubClient {
+    http: Client,
+    // Executes the requests that `http` builds; swappable for tests
+    backend: std::sync::Arc<dyn HttpBackend>,
+    // Auth applied per request, for clients built from an external `Client`
+    // whose default headers can no longer be changed
+    request_token: Option<String>,
+    // Several tokens sharing the quota load; overrides `request_token` and
+    // the default Authorization header when present
+    token_pool: Option<TokenPool>,
+    // User-Agent applied per request for the same reason: GitHub hard-rejects
+    // requests without one, and an external client may not set it; cleared
+    // via `trust_client_user_agent` when the wrapped client has its own
+    request_user_agent: Option<String>,
+    base_url: String,
+    max_retries: u32,
+    retry_base_delay: std::time::Duration,
+    retry_jitter: f64,
+    // Sleep until the quota resets when remaining requests drop below this
+    low_quota_threshold: Option<u32>,
+    // Reject truncated responses instead of returning (and caching) them
+    strict_results: bool,
+    // The most recent X-RateLimit-* headers seen, updated on every search
+    last_rate_limit: std::sync::Mutex<Option<RateLimitInfo>>,
+    // How long the most recent API call took, including retries and body read
+    last_request_duration: std::sync::Mutex<Option<std::time::Duration>>,
+}
+
+// Sent with every request when no custom User-Agent is configured
+const DEFAULT_USER_AGENT: &str = "github_search_tool";
+
+// Give up on requests that take longer than this unless configured otherwise
+const DEFAULT_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
+
+// How many times to retry a rate-limited or failing request before giving up
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+// First backoff delay; doubles on each further attempt
+const DEFAULT_RETRY_BASE_DELAY: std::time::Duration = std::time::Duration::from_secs(1);
+
+// The REST API version each request is pinned to, per GitHub's guidance
+const DEFAULT_API_VERSION: &str = "2022-11-28";
+
+// How much of the backoff delay is randomized away by default; retries are
+// scaled into [1 - jitter, 1] so concurrent searches don't retry in lockstep
+const DEFAULT_RETRY_JITTER: f64 = 0.5;
+
+// Randomize a backoff delay into [delay * (1 - jitter), delay], so callers
+// that hit a shared rate-limit reset don't all fire again simultaneously
+fn apply_jitter(delay: std::time::Duration, jitter: f64) -> std::time::Duration {
+    if jitter <= 0.0 {
+        return delay;
+    }
+    // A timestamp-seeded fraction is random enough to spread out retries
+    // without pulling in a full RNG dependency
+    let rand01 = f64::from(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.subsec_nanos())
+            .unwrap_or(0)
+            % 1000,
+    ) / 1000.0;
+    delay.mul_f64(1.0 - jitter * rand01)
+}
+
+// Send a request, retrying on 403/429 rate-limit responses and transient
+// 5xx server errors. Sleeps for the `Retry-After` duration when GitHub
+// provides one, falling back to exponential backoff otherwise.
+async fn send_with_retry(
+    backend: &dyn HttpBackend,
+    request: reqwest::RequestBuilder,
+    max_attempts: u32,
+    base_delay: std::time::Duration,
+    jitter: f64,
+) -> Result<(reqwest::StatusCode, reqwest::header::HeaderMap, bytes::Bytes), Error> {
+    let request = request.build()?;
+    let mut attempts = 0;
+
+    loop {
+        let attempt = request
+            .try_clone()
+            .ok_or_else(|| Error::Other("Request cannot be cloned for retrying".to_string()))?;
+        let response = backend.execute(attempt).await?;
+        let status_code = response.status;
+
+        // Only retry 403s that are actually rate limiting; genuine permission
+        // denials would fail the same way every time
+        let rate_limited_403 = status_code.eq(&403)
+            && (response.headers.contains_key("Retry-After")
+                || response
+                    .headers
+                    .get("x-ratelimit-remaining")
+                    .and_then(|value| value.to_str().ok())
+                    == Some("0"));
+
+        // 502/503 and friends are usually transient incidents worth retrying
+        let transient_5xx = [500u16, 502, 503, 504].contains(&status_code.as_u16());
+
+        if (rate_limited_403 || status_code.eq(&429) || transient_5xx) && attempts < max_attempts {
+            // Prefer the server-provided Retry-After, otherwise back off exponentially
+            let wait = response
+                .headers
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(std::time::Duration::from_secs)
+                .unwrap_or_else(|| apply_jitter(base_delay * 2u32.pow(attempts), jitter));
+
+            attempts += 1;
+            warn!(
+                "Transient failure ({}), retrying in {:?} (attempt {}/{})",
+                status_code, wait, attempts, max_attempts
+            );
+            tokio::time::sleep(wait).await;
+            continue;
+        }
+
+        return Ok((status_code, response.headers, response.body));
+    }
+}
+
+// Decide whether a 403 is a quota problem or a genuine permission failure,
+// based on the X-RateLimit-* response headers
+fn forbidden_error(headers: &reqwest::header::HeaderMap, raw_body: &[u8]) -> Error {
+    let header_u64 = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok())
+    };
+
+    // GitHub reports secondary (abuse-detection) rate limits in the body
+    // rather than the headers; these need a longer backoff than quota
+    // exhaustion, so they get their own variant
+    let raw_body = String::from_utf8_lossy(raw_body);
+    if raw_body.to_lowercase().contains("secondary rate limit") {
+        return Error::SecondaryRateLimited {
+            retry_after: header_u64("Retry-After").map(std::time::Duration::from_secs),
+        };
+    }
+
+    if header_u64("x-ratelimit-remaining") == Some(0) {
+        return Error::RateLimited {
+            remaining: header_u64("x-ratelimit-remaining").unwrap_or(0) as u32,
+            limit: header_u64("x-ratelimit-limit").unwrap_or(0) as u32,
+            reset: header_u64("x-ratelimit-reset").unwrap_or(0),
+        };
+    }
+
+    Error::Forbidden(raw_body.into_owned())
+}
+
+// Extract the page number of the `rel="next"` / `rel="last"` entry from a Link header
+fn parse_link_page(link_header: &str, rel: &str) -> Option<u32> {
+    for entry in link_header.split(',') {
+        if !entry.contains(&format!("rel=\"{}\"", rel)) {
+            continue;
+        }
+        // Each entry looks like `<https://api.github.com/search/...?q=...&page=3>; rel="next"`
+        let url = entry
+            .split(';')
+            .next()?
+            .trim()
+            .trim_start_matches('<')
+            .trim_end_matches('>');
+        for pair in url.split('?').nth(1)?.split('&') {
+            if let Some(page) = pair.strip_prefix("page=") {
+                return page.parse().ok();
+            }
+        }
+    }
+    None
+}
+
+// Everything configurable about a `GithubClient`, applied on `build()`
+pub struct GithubClientBuilder {
+    token: Option<String>,
+    token_pool: Vec<String>,
+    user_agent: String,
+    base_url: String,
+    timeout: std::time::Duration,
+    max_retries: u32,
+    retry_base_delay: std::time::Duration,
+    retry_jitter: f64,
+    low_quota_threshold: Option<u32>,
+    proxy: Option<reqwest::Proxy>,
+    api_version: String,
+    strict_results: bool,
+    max_response_bytes: usize,
+    backend: Option<std::sync::Arc<dyn HttpBackend>>,
+}
+
+impl GithubClientBuilder {
+    // Authenticate requests with this token
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_owned());
+        self
+    }
+
+    // Spread requests over several tokens to multiply effective quota; takes
+    // precedence over `token`, and auth moves to per-request headers so each
+    // call can carry whichever token currently has the most quota left
+    pub fn token_pool(mut self, tokens: &[&str]) -> Self {
+        self.token_pool = tokens.iter().map(|token| (*token).to_owned()).collect();
+        self
+    }
+
+    // Identify with a custom User-Agent
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = user_agent.to_owned();
+        self
+    }
+
+    // Point at a custom base URL, e.g. a GitHub Enterprise Server instance
+    pub fn base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_owned();
+        self
+    }
+
+    // Abort requests that take longer than this
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    // How many times to retry transient failures (5xx, rate limits)
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    // The first backoff delay; each further attempt doubles it
+    pub fn retry_base_delay(mut self, delay: std::time::Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
+
+    // How much of each backoff delay to randomize away, from 0.0 (fixed
+    // doubling) to 1.0 (anywhere down to zero); clamped into that range
+    pub fn retry_jitter(mut self, jitter: f64) -> Self {
+        self.retry_jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    // Opt in to sleeping until the quota resets whenever fewer than
+    // `threshold` requests remain, instead of risking a hard 403 mid-batch
+    pub fn auto_wait_on_low_quota(mut self, threshold: u32) -> Self {
+        self.low_quota_threshold = Some(threshold);
+        self
+    }
+
+    // Abort reading any response whose body grows past this many bytes,
+    // guarding against memory exhaustion from a misbehaving endpoint
+    pub fn max_response_bytes(mut self, max_response_bytes: usize) -> Self {
+        self.max_response_bytes = max_response_bytes;
+        self
+    }
+
+    // Treat a response with `incomplete_results: true` as an error instead of
+    // caching the truncated list as if it were authoritative
+    pub fn strict_results(mut self) -> Self {
+        self.strict_results = true;
+        self
+    }
+
+    // Route requests through an explicit proxy. Without this, reqwest still
+    // honors the HTTPS_PROXY/HTTP_PROXY (and NO_PROXY) environment variables.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    // Pin requests to a specific REST API version instead of the default
+    pub fn api_version(mut self, api_version: &str) -> Self {
+        self.api_version = api_version.to_owned();
+        self
+    }
+
+    // Execute requests through a custom backend instead of the network,
+    // e.g. `crate::testing::MockBackend` in unit tests
+    pub fn http_backend(mut self, backend: std::sync::Arc<dyn HttpBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    pub fn build(self) -> Result<GithubClient, Error> {
+        // GitHub rejects requests without a User-Agent, so refuse to build
+        // a client that would send an empty one
+        if self.user_agent.trim().is_empty() {
+            return Err(Error::Other("User-Agent must not be empty".to_string()));
+        }
+
+        // With a token pool, auth is attached per request (whichever token
+        // the pool picks) instead of living in the default headers
+        let default_token = if self.token_pool.is_empty() {
+            self.token.as_deref()
+        } else {
+            None
+        };
+        let http = build_http(
+            default_token,
+            &self.user_agent,
+            self.timeout,
+            self.proxy,
+            &self.api_version,
+        )?;
+        // Requests still get built by `http` (for its default headers); only
+        // their execution is routed through the backend
+        let backend = self.backend.unwrap_or_else(|| {
+            std::sync::Arc::new(ReqwestBackend::new(http.clone(), self.max_response_bytes))
+        });
+
+        let token_pool = if self.token_pool.is_empty() {
+            None
+        } else {
+            let tokens: Vec<&str> = self.token_pool.iter().map(String::as_str).collect();
+            Some(TokenPool::new(&tokens))
+        };
+
+        Ok(GithubClient {
+            http,
+            backend,
+            request_token: None,
+            token_pool,
+            request_user_agent: None,
+            base_url: self.base_url,
+            max_retries: self.max_retries,
+            retry_base_delay: self.retry_base_delay,
+            retry_jitter: self.retry_jitter,
+            low_quota_threshold: self.low_quota_threshold,
+            strict_results: self.strict_results,
+            last_rate_limit: std::sync::Mutex::new(None),
+            last_request_duration: std::sync::Mutex::new(None),
+        })
+    }
+}
+
+// Build a reqwest client with the User-Agent (and optionally Authorization) headers set
+fn build_http(
+    token: Option<&str>,
+    user_agent: &str,
+    timeout: std::time::Duration,
+    proxy: Option<reqwest::Proxy>,
+    api_version: &str,
+) -> Result<Client, Error> {
+    let mut headers = reqwest::header::HeaderMap::new();
+
+    if let Some(token) = token {
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {}", token)
+                .parse()
+                .map_err(|e| Error::Other(format!("Token is not a valid header value: {}", e)))?,
+        );
+    }
+    headers.insert(
+        reqwest::header::USER_AGENT,
+        user_agent
+            .parse()
+            .map_err(|e| Error::Other(format!("Invalid User-Agent: {}", e)))?,
+    );
 
-pub async fn search_code(
-    client: &Client,
-    cache: &Cache,            // Add cache for code search as well
-    query: &str,
-    filename: Option<&str>,   // Allow limiting search by specific filenames
-    per_page: Option<&u32>    // Number of results per page
-) -> Result<CodeSearchResponse, anyhow::Error> {
+    // Pin the API version and default media type; individual requests still
+    // override Accept when they need a preview media type
+    headers.insert(
+        reqwest::header::ACCEPT,
+        "application/vnd.github+json"
+            .parse()
+            .expect("static Accept header is valid"),
+    );
+    headers.insert(
+        "X-GitHub-Api-Version",
+        api_version
+            .parse()
+            .map_err(|e| Error::Other(format!("Invalid API version: {}", e)))?,
+    );
 
-    // Build the full query with optional filename filtering
-    let mut full_query = query.to_string();
-    if let Some(fname) = filename {
-        full_query.push_str(&format!(" filename:{}", fname));
+    let mut builder = Client::builder().default_headers(headers).timeout(timeout);
+    if let Some(proxy) = proxy {
+        builder = builder.proxy(proxy);
     }
+    Ok(builder.build()?)
+}
 
-    // Use per_page parameter, defaulting to 10
-    let pp = per_page.unwrap_or(&10);
+// A cloneable handle for aborting in-flight searches, e.g. when a UI user
+// types a new query before the previous one finishes. Cancelling wakes every
+// waiter, and further work observes the flag between page fetches.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    inner: std::sync::Arc<CancellationInner>,
+}
 
-    // Use the full query (query + filters) as the cache key
-    let cache_key = format!("code-{}-{}", full_query, pp);
+#[derive(Default)]
+struct CancellationInner {
+    cancelled: std::sync::atomic::AtomicBool,
+    notify: tokio::sync::Notify,
+}
 
-    // Check the cache for this specific query
-    if let Some(CachedResponse::Code(cached_response)) = cache.get(&cache_key) {
-        println!("Cache hit for code search query: {}", cache_key);
-        return Ok(cached_response);
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
     }
 
-    println!("Cache miss for code search query: {}", cache_key);
-
-    // Query the GitHub Search API (code search endpoint)
-    let response = client
-        .get("https://api.github.com/search/code")
-        .query(&[("q", &full_query)]) // Add query parameters, such as `q=<search_phrase>`
-        .query(&[("per_page", pp)])   // Limit results per page
-        .header("User-Agent", "github_search_tool")
-        .send()
-        .await?;
+    // Flag the token as cancelled and wake everything waiting on it
+    pub fn cancel(&self) {
+        self.inner
+            .cancelled
+            .store(true, std::sync::atomic::Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
 
-    let status_code = response.status();
-    let raw_body = response.text().await?;
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(std::sync::atomic::Ordering::SeqCst)
+    }
 
-    if status_code.eq(&422) {
-        return Err(anyhow!("Invalid query syntax: {}", raw_body));
-    } else if status_code.eq(&401) {
-        return Err(anyhow!("Invalid token: {}", raw_body));
-    } else if status_code.eq(&403) {
-        return Err(anyhow!("Permission denied: {}", raw_body));
-    } else if status_code.is_client_error() {
-        return Err(anyhow!("Unexpected client error: {}", raw_body));
-    } else if status_code.is_server_error() {
-        return Err(anyhow!("Unexpected server error: {}", raw_body));
+    // Resolve once the token is cancelled
+    pub async fn cancelled(&self) {
+        // `notified()` only registers once polled, so enable it explicitly
+        // before checking the flag; otherwise a cancel landing in between
+        // would be missed and this future would hang forever
+        let notified = self.inner.notify.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
+        if self.is_cancelled() {
+            return;
+        }
+        notified.await;
     }
+}
 
-    // Deserialize the response as `CodeSearchResponse`
-    let result: CodeSearchResponse = serde_json::from_str(&raw_body).unwrap();
+// What we last learned about one token's quota from the X-RateLimit-*
+// headers; `remaining: None` means the token has not been used yet
+struct TokenQuota {
+    token: String,
+    remaining: Option<u32>,
+    reset: Option<u64>,
+}
 
-    // Insert the new result into the cache
-    cache.insert(&cache_key, CachedResponse::Code(result.clone()));
+// A set of tokens sharing the work of a large crawl. Each request goes out
+// with the token that has the most quota left, so hitting one token's limit
+// rotates naturally onto the next; once every window has reset the pool
+// starts over. Attribution of response headers to tokens is best-effort
+// under concurrency, which at worst picks a slightly stale candidate.
+struct TokenPool {
+    // The quota table plus the index of the most recently picked token
+    state: std::sync::Mutex<(Vec<TokenQuota>, usize)>,
+}
+
+impl TokenPool {
+    fn new(tokens: &[&str]) -> Self {
+        let entries = tokens
+            .iter()
+            .map(|token| TokenQuota {
+                token: (*token).to_owned(),
+                remaining: None,
+                reset: None,
+            })
+            .collect();
+        Self {
+            state: std::sync::Mutex::new((entries, 0)),
+        }
+    }
 
-    Ok(result)
+    // Pick the token with the most remaining quota. Unused tokens count as
+    // full, and a token whose reset time has passed is fresh again. When
+    // every token is exhausted, the one resetting soonest is returned and
+    // the usual retry/backoff path deals with the 403.
+    fn pick(&self) -> String {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        let mut state = self.state.lock().unwrap();
+        let (entries, last_picked) = &mut *state;
+
+        for entry in entries.iter_mut() {
+            if entry.reset.is_some_and(|reset| reset <= now) {
+                entry.remaining = None;
+                entry.reset = None;
+            }
+        }
+
+        let best = entries
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, entry)| entry.remaining.unwrap_or(u32::MAX));
+        let index = match best {
+            Some((index, entry)) if entry.remaining != Some(0) => index,
+            // All exhausted: least painful is whichever window reopens first
+            _ => entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, entry)| entry.reset.unwrap_or(u64::MAX))
+                .map(|(index, _)| index)
+                .unwrap_or(0),
+        };
+        *last_picked = index;
+        entries[index].token.clone()
+    }
+
+    // Attribute freshly seen rate-limit headers to the last token picked
+    fn record(&self, info: &RateLimitInfo) {
+        let mut state = self.state.lock().unwrap();
+        let (entries, last_picked) = &mut *state;
+        if let Some(entry) = entries.get_mut(*last_picked) {
+            entry.remaining = Some(info.remaining);
+            entry.reset = Some(info.reset);
+        }
+    }
 }
 
-pub async fn search_repositories(
-    client: &Client,
-    cache: &Cache,            // Add cache as a parameter
-    query: &str,
-    per_page: Option<&u32>
-) -> Result<SearchResponse, anyhow::Error> {
+// The parsed outcome of one search request. `data` is `None` when GitHub
+// answered 304 Not Modified, meaning the caller's cached copy is current.
+struct FetchedSearch<T> {
+    data: Option<T>,
+    etag: Option<String>,
+    next_page: Option<u32>,
+    last_page: Option<u32>,
+}
+
+// The request-level knobs for code search, shared by the raw-string and
+// typed entry points so neither needs them as positional arguments
+#[derive(Default)]
+struct CodeOptions<'a> {
+    highlight: bool,         // Ask for text-match fragments
+    sort: Option<&'a str>,   // Only `indexed` is supported by GitHub
+    order: Option<&'a str>,  // `asc` or `desc`
+}
 
-    let pp = per_page.unwrap_or(&10);
-    let cache_key = format!("{}-{}", query, pp);
+// Glue between a typed response and its `CachedResponse` variant, letting
+// one generic helper (`cached_search`) drive the whole cache flow instead
+// of each endpoint repeating it
+trait Cacheable: Clone {
+    fn into_cached(self) -> CachedResponse;
+    fn from_cached(cached: CachedResponse) -> Option<Self>;
 
-    // Check if the query result is in the cache
-    if let Some(CachedResponse::Search(cached_response)) = cache.get(&cache_key) {
-        println!("Cache hit for query: {}", cache_key);
-        return Ok(cached_response); // Return the cached response
+    // Whether GitHub flagged the result set as truncated; endpoints
+    // without the flag never are
+    fn incomplete_results(&self) -> bool {
+        false
     }
+}
 
-    println!("Cache miss for query: {}", query);
+impl Cacheable for SearchResponse {
+    fn into_cached(self) -> CachedResponse {
+        CachedResponse::Search(self)
+    }
 
-    let response = client
-        .get("https://api.github.com/search/repositories")
-        .query(&[("q", query)]) // Add the query as a GET parameter
-        .query(&[("per_page", pp)]) // Add per_page as a GET parameter
-        .send()
-        .await?;
+    fn from_cached(cached: CachedResponse) -> Option<Self> {
+        match cached {
+            CachedResponse::Search(response) => Some(response),
+            _ => None,
+        }
+    }
 
-    let status_code = response.status();
-    let raw_body = response.text().await?;
+    fn incomplete_results(&self) -> bool {
+        self.incomplete_results
+    }
+}
 
-    if status_code.eq(&422) {
-        return Err(anyhow!("Invalid query syntax: {}", raw_body));
-    } else if status_code.eq(&401) {
-        return Err(anyhow!("Invalid token: {}", raw_body));
-    } else if status_code.eq(&403) {
-        return Err(anyhow!("Permission denied: {}", raw_body));
-    } else if status_code.is_client_error() {
-        return Err(anyhow!("Unexpected client error: {}", raw_body));
-    } else if status_code.is_server_error() {
-        return Err(anyhow!("Unexpected server error: {}", raw_body));
+impl Cacheable for CodeSearchResponse {
+    fn into_cached(self) -> CachedResponse {
+        CachedResponse::Code(self)
     }
 
-    let result: SearchResponse = serde_json::from_str(&raw_body).unwrap();
+    fn from_cached(cached: CachedResponse) -> Option<Self> {
+        match cached {
+            CachedResponse::Code(response) => Some(response),
+            _ => None,
+        }
+    }
+
+    fn incomplete_results(&self) -> bool {
+        self.incomplete_results
+    }
+}
+
+impl Cacheable for CommitSearchResponse {
+    fn into_cached(self) -> CachedResponse {
+        CachedResponse::Commits(self)
+    }
 
-    // Insert the new result into the cache
-    cache.insert(&cache_key, CachedResponse::Search(result.clone()));
+    fn from_cached(cached: CachedResponse) -> Option<Self> {
+        match cached {
+            CachedResponse::Commits(response) => Some(response),
+            _ => None,
+        }
+    }
 
-    Ok(result)
+    fn incomplete_results(&self) -> bool {
+        self.incomplete_results
+    }
 }
 
-pub async fn check_rate_limit(client: &Client) -> Result<RateLimit, anyhow::Error> {
-    // Make the request to the rate limit endpoint
-    let response = client
-        .get("https://api.github.com/rate_limit")
-        .send()
-        .await?
-        .json::<RateLimit>() // Deserialize JSON into `RateLimit`
-        .await?;
+impl Cacheable for IssueSearchResponse {
+    fn into_cached(self) -> CachedResponse {
+        CachedResponse::Issues(self)
+    }
+
+    fn from_cached(cached: CachedResponse) -> Option<Self> {
+        match cached {
+            CachedResponse::Issues(response) => Some(response),
+            _ => None,
+        }
+    }
+
+    fn incomplete_results(&self) -> bool {
+        self.incomplete_results
+    }
+}
+
+impl Cacheable for TopicSearchResponse {
+    fn into_cached(self) -> CachedResponse {
+        CachedResponse::Topics(self)
+    }
+
+    fn from_cached(cached: CachedResponse) -> Option<Self> {
+        match cached {
+            CachedResponse::Topics(response) => Some(response),
+            _ => None,
+        }
+    }
+
+    fn incomplete_results(&self) -> bool {
+        self.incomplete_results
+    }
+}
+
+impl Cacheable for RepositoryDetails {
+    fn into_cached(self) -> CachedResponse {
+        CachedResponse::Repository(Box::new(self))
+    }
+
+    fn from_cached(cached: CachedResponse) -> Option<Self> {
+        match cached {
+            CachedResponse::Repository(response) => Some(*response),
+            _ => None,
+        }
+    }
+}
+
+impl GithubClient {
+    // Start configuring a client; defaults to the public API, anonymous
+    // access, and a 30 second timeout
+    pub fn builder() -> GithubClientBuilder {
+        GithubClientBuilder {
+            token: None,
+            token_pool: Vec::new(),
+            user_agent: DEFAULT_USER_AGENT.to_owned(),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            timeout: DEFAULT_TIMEOUT,
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            retry_jitter: DEFAULT_RETRY_JITTER,
+            low_quota_threshold: None,
+            proxy: None,
+            strict_results: false,
+            max_response_bytes: crate::http_backend::DEFAULT_MAX_RESPONSE_BYTES,
+            api_version: DEFAULT_API_VERSION.to_owned(),
+            backend: None,
+        }
+    }
+
+    // Build an authenticated client pointed at the public GitHub API
+    pub fn new(token: &str) -> Result<Self, Error> {
+        Self::with_user_agent(token, DEFAULT_USER_AGENT)
+    }
+
+    // Build an authenticated client that identifies itself with a custom User-Agent
+    pub fn with_user_agent(token: &str, user_agent: &str) -> Result<Self, Error> {
+        Self::builder().token(token).user_agent(user_agent).build()
+    }
+
+    // Build an unauthenticated client for public searches at the lower rate limit
+    pub fn anonymous() -> Result<Self, Error> {
+        Self::builder().build()
+    }
+
+    // Wrap an app-wide reqwest `Client`, reusing its connection pool, proxy,
+    // and TLS settings. The token (when given) is attached per request, since
+    // an existing client's default headers cannot be amended after the fact.
+    pub fn from_client(http: Client, token: Option<&str>) -> Self {
+        Self {
+            backend: std::sync::Arc::new(http.clone()),
+            http,
+            request_token: token.map(str::to_owned),
+            token_pool: None,
+            request_user_agent: Some(DEFAULT_USER_AGENT.to_owned()),
+            base_url: DEFAULT_BASE_URL.to_owned(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            retry_jitter: DEFAULT_RETRY_JITTER,
+            low_quota_threshold: None,
+            strict_results: false,
+            last_rate_limit: std::sync::Mutex::new(None),
+            last_request_duration: std::sync::Mutex::new(None),
+        }
+    }
+
+    // Build a client pointed at a custom base URL, e.g. `https://github.mycorp.com/api/v3`
+    pub fn with_base_url(http: Client, base_url: &str) -> Self {
+        Self {
+            backend: std::sync::Arc::new(http.clone()),
+            http,
+            request_token: None,
+            token_pool: None,
+            request_user_agent: Some(DEFAULT_USER_AGENT.to_owned()),
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            max_retries: DEFAULT_MAX_RETRIES,
+            retry_base_delay: DEFAULT_RETRY_BASE_DELAY,
+            retry_jitter: DEFAULT_RETRY_JITTER,
+            low_quota_threshold: None,
+            strict_results: false,
+            last_rate_limit: std::sync::Mutex::new(None),
+            last_request_duration: std::sync::Mutex::new(None),
+        }
+    }
+
+    // Trust the wrapped client's own User-Agent default header instead of
+    // attaching the crate fallback to each request. Per-request headers win
+    // over a client's defaults in reqwest, so without this a deliberately
+    // configured User-Agent on a wrapped client would be silently replaced.
+    pub fn trust_client_user_agent(mut self) -> Self {
+        self.request_user_agent = None;
+        self
+    }
+
+    // Join an endpoint path onto the configured base URL
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    // Start a GET request against an endpoint, layering in the per-request
+    // token when one is configured (see `from_client`)
+    fn request(&self, path: &str) -> reqwest::RequestBuilder {
+        self.request_absolute(&self.url(path))
+    }
+
+    // The same per-request layering for URLs the API hands back whole,
+    // e.g. a code-search hit's contents URL
+    fn request_absolute(&self, url: &str) -> reqwest::RequestBuilder {
+        let request = self.http.get(url);
+        // The pool picks a token per request; otherwise fall back to the
+        // fixed per-request token, if any
+        let request = if let Some(pool) = &self.token_pool {
+            request.bearer_auth(pool.pick())
+        } else {
+            match &self.request_token {
+                Some(token) => request.bearer_auth(token),
+                None => request,
+            }
+        };
+        // GitHub returns 403 for requests without a User-Agent, so wrapped
+        // clients fall back to the crate default rather than failing flakily
+        match &self.request_user_agent {
+            Some(user_agent) => request.header(reqwest::header::USER_AGENT, user_agent),
+            None => request,
+        }
+    }
+
+    // Remember the rate-limit headers from a response, so callers can
+    // throttle without spending a request on `check_rate_limit`
+    fn record_rate_limit(&self, headers: &reqwest::header::HeaderMap) {
+        let header_u64 = |name: &str| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+
+        if let (Some(limit), Some(remaining), Some(reset)) = (
+            header_u64("x-ratelimit-limit"),
+            header_u64("x-ratelimit-remaining"),
+            header_u64("x-ratelimit-reset"),
+        ) {
+            let info = RateLimitInfo {
+                limit: limit as u32,
+                remaining: remaining as u32,
+                reset,
+            };
+            if let Some(pool) = &self.token_pool {
+                pool.record(&info);
+            }
+            *self.last_rate_limit.lock().unwrap() = Some(info);
+        }
+    }
+
+    // The rate-limit state as of the most recent search call, if any
+    pub fn last_rate_limit(&self) -> Option<RateLimitInfo> {
+        self.last_rate_limit.lock().unwrap().clone()
+    }
+
+    // How long the most recent API call took, measured around sending the
+    // request (with any retries) and reading the body; `None` before the
+    // first call. Centralizes latency metrics without timing every call site.
+    pub fn last_request_duration(&self) -> Option<std::time::Duration> {
+        *self.last_request_duration.lock().unwrap()
+    }
+
+    // When auto-wait is enabled and quota is nearly gone, sleep until the
+    // window resets before issuing the next request
+    async fn wait_if_low_quota(&self) {
+        let Some(threshold) = self.low_quota_threshold else {
+            return;
+        };
+        let Some(info) = self.last_rate_limit() else {
+            return;
+        };
+        if info.remaining >= threshold {
+            return;
+        }
+
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        let wait = info.reset.saturating_sub(now);
+        if wait > 0 {
+            warn!(
+                "Only {} requests remaining; waiting {}s for the quota to reset",
+                info.remaining, wait
+            );
+            // One extra second of slack for clock skew
+            tokio::time::sleep(std::time::Duration::from_secs(wait + 1)).await;
+        }
+    }
+
+    // The plumbing shared by every search endpoint: wait out low quota, send
+    // with retries, record rate-limit headers, and map error statuses. This
+    // is the only place a response status becomes an `Error`. The body stays
+    // as reference-counted bytes so raw consumers avoid an extra copy.
+    async fn fetch_search(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<FetchedSearch<bytes::Bytes>, Error> {
+        self.wait_if_low_quota().await;
+
+        let started = std::time::Instant::now();
+        let (status_code, headers, raw_body) =
+            send_with_retry(
+                self.backend.as_ref(),
+                request,
+                self.max_retries,
+                self.retry_base_delay,
+                self.retry_jitter,
+            )
+            .await?;
+        let elapsed = started.elapsed();
+        *self.last_request_duration.lock().unwrap() = Some(elapsed);
+        self.record_rate_limit(&headers);
+
+        // Inherits the endpoint/query fields of the calling method's span
+        debug!(
+            status = %status_code,
+            elapsed_ms = elapsed.as_millis() as u64,
+            rate_limit_remaining = self.last_rate_limit().map(|info| info.remaining),
+            "GitHub API response"
+        );
+
+        // 304 carries no body; the caller decides what to reuse
+        if status_code.eq(&304) {
+            return Ok(FetchedSearch {
+                data: None,
+                etag: None,
+                next_page: None,
+                last_page: None,
+            });
+        }
+
+        // 403 needs the rate-limit headers to classify; everything else
+        // becomes a typed `ApiError` callers can match on by status
+        if status_code.eq(&403) {
+            return Err(forbidden_error(&headers, &raw_body));
+        } else if status_code.is_client_error() || status_code.is_server_error() {
+            return Err(Error::ApiError {
+                status: status_code,
+                body: String::from_utf8_lossy(&raw_body).into_owned(),
+            });
+        }
+
+        let etag = headers
+            .get("ETag")
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_owned);
+
+        // Pull next/last page numbers out of the Link header, when present
+        let link = headers
+            .get("Link")
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("");
+
+        Ok(FetchedSearch {
+            data: Some(raw_body),
+            etag,
+            next_page: parse_link_page(link, "next"),
+            last_page: parse_link_page(link, "last"),
+        })
+    }
+
+    // GitHub sets `incomplete_results` when its search timed out. Always
+    // flag that in the logs; in strict mode additionally refuse the
+    // truncated result set before it reaches the cache.
+    fn check_complete(&self, incomplete_results: bool) -> Result<(), Error> {
+        if incomplete_results {
+            warn!("GitHub returned incomplete results (search timed out server-side)");
+            if self.strict_results {
+                return Err(Error::IncompleteResults);
+            }
+        }
+        Ok(())
+    }
+
+    // Remember a 422 under the query's cache key, so resubmitting the same
+    // malformed query replays the error instead of spending quota; any other
+    // error passes through without being cached
+    async fn cache_invalid_query(&self, cache: &Cache, cache_key: &str, err: &Error) {
+        if let Error::ApiError { status, body } = err
+            && status.as_u16() == 422
+        {
+            cache
+                .insert(
+                    cache_key,
+                    CachedResponse::Invalid {
+                        status: status.as_u16(),
+                        body: body.clone(),
+                    },
+                )
+                .await;
+        }
+    }
+
+    // `fetch_search` plus deserialization, parsing straight from the bytes
+    // rather than round-tripping through an owned `String`
+    async fn execute_search<T: serde::de::DeserializeOwned>(
+        &self,
+        request: reqwest::RequestBuilder,
+    ) -> Result<FetchedSearch<T>, Error> {
+        let fetched = self.fetch_search(request).await?;
+
+        let data = match &fetched.data {
+            Some(raw_body) => Some(serde_json::from_slice(raw_body).map_err(|e| {
+                Error::Other(format!(
+                    "Failed to parse response: {} — body: {}",
+                    e,
+                    String::from_utf8_lossy(raw_body)
+                ))
+            })?),
+            None => None,
+        };
+
+        Ok(FetchedSearch {
+            data,
+            etag: fetched.etag,
+            next_page: fetched.next_page,
+            last_page: fetched.last_page,
+        })
+    }
+
+    // The cache flow every cached endpoint shares: serve a fresh hit, replay
+    // a remembered 422, revalidate a stale entry by ETag (handling the free
+    // 304), and store fresh results with their ETag. `T` picks the
+    // `CachedResponse` variant through the `Cacheable` glue; the endpoint
+    // context in the log events comes from the caller's tracing span.
+    async fn cached_search<T>(
+        &self,
+        cache: &Cache,
+        cache_key: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, Error>
+    where
+        T: Cacheable + serde::de::DeserializeOwned,
+    {
+        match cache.get(cache_key).await {
+            // A remembered bad query: replay the 422 instead of refetching
+            Some(CachedResponse::Invalid { status, body }) => {
+                debug!("Negative cache hit for query: {}", cache_key);
+                return Err(Error::ApiError {
+                    status: reqwest::StatusCode::from_u16(status)
+                        .unwrap_or(reqwest::StatusCode::UNPROCESSABLE_ENTITY),
+                    body,
+                });
+            }
+            Some(cached) => {
+                if let Some(response) = T::from_cached(cached) {
+                    debug!("Cache hit for query: {}", cache_key);
+                    return Ok(response);
+                }
+            }
+            None => {}
+        }
+
+        debug!("Cache miss for query: {}", cache_key);
+
+        // Revalidate an expired entry instead of refetching when we have its ETag
+        let stale = match cache.get_stale(cache_key).await {
+            Some((cached, Some(etag))) => T::from_cached(cached).map(|response| (response, etag)),
+            _ => None,
+        };
+        let request = match &stale {
+            Some((_, etag)) => request.header("If-None-Match", etag),
+            None => request,
+        };
+
+        let fetched = match self.execute_search::<T>(request).await {
+            Ok(fetched) => fetched,
+            Err(err) => {
+                self.cache_invalid_query(cache, cache_key, &err).await;
+                return Err(err);
+            }
+        };
+
+        let Some(result) = fetched.data else {
+            // 304: our cached copy is still current and the request was free
+            if let Some((response, _)) = stale {
+                debug!("Cache revalidated for query: {}", cache_key);
+                cache.touch(cache_key).await;
+                return Ok(response);
+            }
+            return Err(Error::Other(
+                "Got 304 Not Modified without a cached entry".to_string(),
+            ));
+        };
+
+        self.check_complete(result.incomplete_results())?;
+
+        // Remember the new result and its ETag for future revalidation
+        cache
+            .insert_with_etag(cache_key, result.clone().into_cached(), fetched.etag)
+            .await;
+
+        Ok(result)
+    }
+
+    // The span carries the endpoint and query; the response event inside
+    // `fetch_search` adds status and remaining quota
+    pub async fn search_code(
+        &self,
+        cache: &Cache, // Add cache for code search as well
+        query: &str,
+        filename: Option<&str>, // Allow limiting search by specific filenames
+        per_page: impl Into<Option<u32>>, // Number of results per page (capped at 100)
+        page: impl Into<Option<u32>>,     // Which page of results to fetch
+    ) -> Result<CodeSearchResponse, Error> {
+        // Highlighting and sort/order live on `CodeSearchQuery`; this raw
+        // entry point uses the defaults (plain hits, best-match order)
+        self.search_code_with(cache, query, filename, per_page.into(), page.into(), CodeOptions::default())
+            .await
+    }
+
+    // The shared code-search implementation behind the raw and typed entry
+    // points; `options` carries the request-level knobs
+    #[tracing::instrument(skip(self, cache, per_page, page, options), fields(endpoint = "/search/code"))]
+    async fn search_code_with(
+        &self,
+        cache: &Cache,
+        query: &str,
+        filename: Option<&str>,
+        per_page: Option<u32>,
+        page: Option<u32>,
+        options: CodeOptions<'_>,
+    ) -> Result<CodeSearchResponse, Error> {
+        let CodeOptions { highlight, sort, order } = options;
+        // Reject unsupported sort/order values before spending a request on them
+        if let Some(sort) = sort
+            && sort != "indexed"
+        {
+            return Err(Error::Other(format!(
+                "Invalid sort '{}': code search only supports indexed",
+                sort
+            )));
+        }
+        if let Some(order) = order
+            && !["asc", "desc"].contains(&order)
+        {
+            return Err(Error::Other(format!("Invalid order '{}': expected asc or desc", order)));
+        }
+
+        // Build the full query with optional filename filtering
+        let mut full_query = query.to_string();
+        if let Some(fname) = filename {
+            full_query.push_str(&format!(" filename:{}", fname));
+        }
+        validate_query(&full_query)?;
+
+        // Use per_page parameter, defaulting to 10 and capped at GitHub's max of 100,
+        // and page, defaulting to 1
+        let pp = per_page.unwrap_or(10).min(100);
+        let pg = page.unwrap_or(1);
+
+        // Use the full query (query + filters + page) as the cache key;
+        // highlighted and plain responses have different shapes, so keep them apart
+        let cache_key = format!(
+            "code-{}-{}-{}-{}-{}-{}",
+            normalize_query(&full_query),
+            pp,
+            pg,
+            highlight,
+            sort.unwrap_or(""),
+            order.unwrap_or("")
+        );
+
+        // Query the GitHub Search API (code search endpoint)
+        let request = self.request("/search/code")
+            .query(&[("q", &full_query)]) // Add query parameters, such as `q=<search_phrase>`
+            .query(&[("per_page", pp)]) // Limit results per page
+            .query(&[("page", pg)]); // Fetch the requested page
+
+        // Opt in to text-match metadata when the caller wants highlighting
+        let request = if highlight {
+            request.header("Accept", "application/vnd.github.text-match+json")
+        } else {
+            request
+        };
+
+        // Only send sort/order when the caller asked for them
+        let request = match sort {
+            Some(sort) => request.query(&[("sort", sort)]),
+            None => request,
+        };
+        let request = match order {
+            Some(order) => request.query(&[("order", order)]),
+            None => request,
+        };
+
+        self.cached_search(cache, &cache_key, request).await
+    }
+
+    // Like `search_code`, but takes the typed builder instead of raw strings;
+    // highlighting and sort/order come from the builder too
+    pub async fn search_code_query(
+        &self,
+        cache: &Cache,
+        query: &CodeSearchQuery,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<CodeSearchResponse, Error> {
+        // The builder already folds filename into the query string
+        let options = CodeOptions {
+            highlight: query.highlight,
+            sort: query.sort.as_deref(),
+            order: query.order.as_deref(),
+        };
+        self.search_code_with(cache, &query.to_query_string(), None, per_page.into(), page.into(), options)
+            .await
+    }
+
+    // Search commit messages across GitHub; needs the cloak-preview Accept header
+    #[tracing::instrument(skip(self, cache, per_page, page), fields(endpoint = "/search/commits"))]
+    pub async fn search_commits(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<CommitSearchResponse, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+        validate_query(query)?;
+        let cache_key = format!("commits-{}-{}-{}", normalize_query(query), pp, pg);
+
+        let request = self.request("/search/commits")
+            // Commit search is behind this preview media type
+            .header("Accept", "application/vnd.github.cloak-preview+json")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        self.cached_search(cache, &cache_key, request).await
+    }
+
+    // Search issues and pull requests across GitHub
+    #[tracing::instrument(skip(self, cache, per_page, page), fields(endpoint = "/search/issues"))]
+    pub async fn search_issues(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<IssueSearchResponse, Error> {
+        // GitHub caps per_page at 100, so silently clamp larger values
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+        validate_query(query)?;
+        let cache_key = format!("issues-{}-{}-{}", normalize_query(query), pp, pg);
+
+        let request = self.request("/search/issues")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        self.cached_search(cache, &cache_key, request).await
+    }
+
+    // Search topic metadata directly, e.g. for topic autocompletion; needs
+    // the mercy-preview Accept header like the topics array on repositories
+    #[tracing::instrument(skip(self, cache, per_page, page), fields(endpoint = "/search/topics"))]
+    pub async fn search_topics(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<TopicSearchResponse, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+        validate_query(query)?;
+        let cache_key = format!("topics-{}-{}-{}", normalize_query(query), pp, pg);
+
+        let request = self.request("/search/topics")
+            // Topic search is behind this preview media type
+            .header("Accept", "application/vnd.github.mercy-preview+json")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        self.cached_search(cache, &cache_key, request).await
+    }
+
+    #[tracing::instrument(skip(self, cache, per_page, page), fields(endpoint = "/search/repositories"))]
+    pub async fn search_repositories(
+        &self,
+        cache: &Cache, // Add cache as a parameter
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+        sort: Option<&str>,  // One of `stars`, `forks`, `updated`
+        order: Option<&str>, // `asc` or `desc`
+    ) -> Result<SearchResponse, Error> {
+        // Reject unsupported sort/order values before spending a request on them
+        if let Some(sort) = sort
+            && !["stars", "forks", "updated"].contains(&sort)
+        {
+            return Err(Error::Other(format!(
+                "Invalid sort '{}': expected one of stars, forks, updated",
+                sort
+            )));
+        }
+        if let Some(order) = order
+            && !["asc", "desc"].contains(&order)
+        {
+            return Err(Error::Other(format!("Invalid order '{}': expected asc or desc", order)));
+        }
+
+        // GitHub caps per_page at 100, so silently clamp larger values
+        let pp = per_page.into().unwrap_or(10).min(100);
+        // GitHub pages are 1-based, so treat page 0 as the first page
+        let pg = page.into().unwrap_or(1).max(1);
+
+        validate_query(query)?;
+
+        // Refuse pages past the 1000-result cap up front; GitHub would 422 anyway
+        if (pg - 1).saturating_mul(pp) >= SEARCH_RESULT_CAP {
+            return Err(Error::ResultLimitReached);
+        }
+
+        // Include the page (and any sort/order) so variants of the same query don't collide
+        let cache_key = format!(
+            "{}-{}-{}-{}-{}",
+            normalize_query(query),
+            pp,
+            pg,
+            sort.unwrap_or(""),
+            order.unwrap_or("")
+        );
+
+        let request = self.request("/search/repositories")
+            // Ask GitHub to include the `topics` array in results
+            .header("Accept", "application/vnd.github.mercy-preview+json")
+            .query(&[("q", query)]) // Add the query as a GET parameter
+            .query(&[("per_page", pp)]) // Add per_page as a GET parameter
+            .query(&[("page", pg)]); // Add page as a GET parameter
+
+        // Only send sort/order when the caller asked for them
+        let request = match sort {
+            Some(sort) => request.query(&[("sort", sort)]),
+            None => request,
+        };
+        let request = match order {
+            Some(order) => request.query(&[("order", order)]),
+            None => request,
+        };
+
+        self.cached_search(cache, &cache_key, request).await
+    }
+
+    // Incremental polling: only return repositories pushed since `since`,
+    // newest first. The injected `pushed` filter overrides any `pushed_after`
+    // already set on the query, so repeated polls can reuse one base query.
+    pub async fn search_repositories_since(
+        &self,
+        cache: &Cache,
+        query: &GithubSearchQuery,
+        since: chrono::DateTime<chrono::Utc>,
+    ) -> Result<SearchResponse, Error> {
+        let mut query = query.clone();
+        // Keep the time of day: `pushed:` accepts full ISO-8601 timestamps,
+        // and a date-only cutoff would re-match everything pushed earlier
+        // the same day on intra-day polls
+        query.pushed_after = Some(since.format("%Y-%m-%dT%H:%M:%SZ").to_string());
+
+        self.search_repositories(
+            cache,
+            &query.to_query_string(),
+            None,
+            None,
+            Some("updated"),
+            Some("desc"),
+        )
+        .await
+    }
+
+    // Like `search_repositories`, but aborts promptly with `Error::Cancelled`
+    // when the token fires, even while the request is still in flight
+    #[allow(clippy::too_many_arguments)]
+    pub async fn search_repositories_cancellable(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+        sort: Option<&str>,
+        order: Option<&str>,
+        token: &CancellationToken,
+    ) -> Result<SearchResponse, Error> {
+        if token.is_cancelled() {
+            return Err(Error::Cancelled);
+        }
+
+        tokio::select! {
+            result = self.search_repositories(cache, query, per_page, page, sort, order) => result,
+            _ = token.cancelled() => Err(Error::Cancelled),
+        }
+    }
+
+    // Like `search_repositories_stream`, but stops at the next item boundary
+    // once the token fires, yielding a final `Error::Cancelled`. No further
+    // page is fetched after cancellation.
+    pub fn search_repositories_stream_cancellable<'a>(
+        &'a self,
+        cache: &'a Cache,
+        query: &'a str,
+        per_page: impl Into<Option<u32>>,
+        token: CancellationToken,
+    ) -> impl Stream<Item = Result<Repo, Error>> + 'a {
+        self.search_repositories_stream(cache, query, per_page)
+            .scan(false, move |cancelled_emitted, item| {
+                let out = if token.is_cancelled() {
+                    if *cancelled_emitted {
+                        None // Ends the stream
+                    } else {
+                        *cancelled_emitted = true;
+                        Some(Err(Error::Cancelled))
+                    }
+                } else {
+                    Some(item)
+                };
+                futures::future::ready(out)
+            })
+    }
+
+    // Run many searches with at most `concurrency` requests in flight at once,
+    // returning results in the same order as the input queries
+    pub async fn search_many(
+        &self,
+        cache: &Cache,
+        queries: Vec<GithubSearchQuery>,
+        concurrency: usize,
+    ) -> Vec<Result<SearchResponse, Error>> {
+        stream::iter(queries)
+            .map(|query| async move {
+                self.search_repositories(cache, &query.to_query_string(), None, None, None, None)
+                    .await
+            })
+            // `buffered` bounds the in-flight requests while preserving input order
+            .buffered(concurrency.max(1))
+            .collect()
+            .await
+    }
+
+    // Search repositories and code for the same term and merge the hits into
+    // one relevance-ranked list. GitHub's scores are only comparable within a
+    // single endpoint, so each list is normalized by its own top score (every
+    // endpoint's best hit ranks 1.0) before merging in descending order.
+    pub async fn search_combined(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<Vec<CombinedResult>, Error> {
+        let pp = per_page.into();
+        let pg = page.into();
+        let (repos, code) = futures::join!(
+            self.search_repositories(cache, query, pp, pg, None, None),
+            self.search_code(cache, query, None, pp, pg),
+        );
+        let (repos, code) = (repos?, code?);
+
+        // Dividing by the endpoint's best score maps each list onto 0..=1
+        let top_score = |best: f64| if best > 0.0 { best } else { 1.0 };
+        let repo_top = top_score(repos.items.iter().fold(0.0f64, |max, repo| max.max(repo.score)));
+        let code_top = top_score(code.items.iter().fold(0.0f64, |max, file| max.max(file.score)));
+
+        let mut ranked: Vec<(f64, CombinedResult)> = repos
+            .items
+            .into_iter()
+            .map(|repo| (repo.score / repo_top, CombinedResult::Repository(repo)))
+            .chain(
+                code.items
+                    .into_iter()
+                    .map(|file| (file.score / code_top, CombinedResult::Code(file))),
+            )
+            .collect();
+        // The sort is stable, so equal-scoring repositories stay ahead of code hits
+        ranked.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(ranked.into_iter().map(|(_, result)| result).collect())
+    }
+
+    // Like `search_repositories`, but also returns pagination info parsed from the Link header.
+    // Skips the cache, since the Link header cannot be reconstructed from a cached body.
+    pub async fn search_repositories_paginated(
+        &self,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<Paginated<SearchResponse>, Error> {
+        // GitHub caps per_page at 100, so silently clamp larger values
+        let pp = per_page.into().unwrap_or(10).min(100);
+        // GitHub pages are 1-based, so treat page 0 as the first page
+        let pg = page.into().unwrap_or(1).max(1);
+
+        // Refuse pages past the 1000-result cap up front; GitHub would 422 anyway
+        if (pg - 1).saturating_mul(pp) >= SEARCH_RESULT_CAP {
+            return Err(Error::ResultLimitReached);
+        }
+
+        let request = self.request("/search/repositories")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        let fetched = self.execute_search::<SearchResponse>(request).await?;
+
+        // No If-None-Match was sent, so a 304 (and an empty body) cannot happen here
+        let data = fetched
+            .data
+            .ok_or_else(|| Error::Other("Got 304 Not Modified without a cached entry".to_string()))?;
+
+        // Drop a next page that would only hit the 1000-result cap
+        let next_page = fetched
+            .next_page
+            .filter(|next| (next - 1).saturating_mul(pp) < SEARCH_RESULT_CAP);
+
+        Ok(Paginated {
+            data,
+            next_page,
+            last_page: fetched.last_page,
+        })
+    }
+
+    // Like `search_repositories`, but tolerates malformed entries: each item
+    // is deserialized individually, and ones with unexpected shapes are
+    // skipped (and counted) instead of failing the whole page. Skips the
+    // cache, since the skip count is not part of the cached response shape.
+    pub async fn search_repositories_lenient(
+        &self,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<LenientSearchResponse, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+
+        let request = self.request("/search/repositories")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        let fetched = self.fetch_search(request).await?;
+        let raw_body = fetched
+            .data
+            .ok_or_else(|| Error::Other("Got 304 Not Modified without a cached entry".to_string()))?;
+
+        // Parse the envelope loosely first, then each item on its own
+        let value: serde_json::Value = serde_json::from_slice(&raw_body)
+            .map_err(|e| Error::Other(format!("Failed to parse response: {}", e)))?;
+
+        let total_count = value.get("total_count").and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let incomplete_results = value
+            .get("incomplete_results")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+
+        let mut items = Vec::new();
+        let mut skipped = 0;
+        if let Some(raw_items) = value.get("items").and_then(|v| v.as_array()) {
+            for item in raw_items {
+                match serde_json::from_value::<Repo>(item.clone()) {
+                    Ok(repo) => items.push(repo),
+                    Err(e) => {
+                        skipped += 1;
+                        warn!("Skipping malformed search item: {}", e);
+                    }
+                }
+            }
+        }
+
+        Ok(LenientSearchResponse {
+            response: SearchResponse {
+                total_count,
+                incomplete_results,
+                items,
+            },
+            skipped,
+        })
+    }
+
+    // Build the exact URL a repository search would request, without sending
+    // it. Handy for debugging a query before spending rate limit on it.
+    pub fn preview_search(
+        &self,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<String, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+
+        let request = self.request("/search/repositories")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)])
+            .build()
+            .map_err(|e| Error::Other(format!("Failed to build request: {}", e)))?;
+
+        Ok(request.url().to_string())
+    }
+
+    // Like `search_repositories`, but deserializes only a minimal subset of
+    // each item. Skips the cache, since the minimal shape would collide with
+    // the full one under the same key.
+    pub async fn search_repositories_minimal(
+        &self,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<MinimalSearchResponse, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+
+        let request = self.request("/search/repositories")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        let fetched = self.execute_search::<MinimalSearchResponse>(request).await?;
+        fetched
+            .data
+            .ok_or_else(|| Error::Other("Got 304 Not Modified without a cached entry".to_string()))
+    }
+
+    // Fetch one page of repository results as the raw JSON bytes, skipping
+    // the cache and deserialization entirely. For high-throughput callers
+    // that forward or lazily parse the body themselves.
+    pub async fn search_repositories_raw(
+        &self,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<bytes::Bytes, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+
+        let request = self.request("/search/repositories")
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)]);
+
+        let fetched = self.fetch_search(request).await?;
+        fetched
+            .data
+            .ok_or_else(|| Error::Other("Got 304 Not Modified without a cached entry".to_string()))
+    }
+
+    // Like `search_repositories_paginated`, but also hands back a resume
+    // token pointing at the next page, which can be serialized and later fed
+    // to `resume_search` to pick the crawl back up where it stopped
+    pub async fn search_repositories_resumable(
+        &self,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<(SearchResponse, Option<ResumeToken>), Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let paginated = self.search_repositories_paginated(query, pp, page).await?;
 
-    if response.rate.remaining < 1 {
-        return Err(anyhow!(
-            "{} requests remaining (out of {}). Limit resets at {}.",
-                response.rate.remaining,
-                response.rate.limit,
-                chrono::NaiveDateTime::from_timestamp(response.rate.reset as i64, 0)
-                    .format("%Y-%m-%d %H:%M:%S")
-        ));
-    }
+        let token = paginated.next_page.map(|next_page| ResumeToken {
+            query: query.to_string(),
+            per_page: pp,
+            next_page,
+        });
 
-    Ok(response)
-}
\ No newline at end of file
+        Ok((paginated.data, token))
+    }
+
+    // Continue an interrupted crawl from a previously saved resume token
+    pub async fn resume_search(
+        &self,
+        token: &ResumeToken,
+    ) -> Result<(SearchResponse, Option<ResumeToken>), Error> {
+        self.search_repositories_resumable(&token.query, token.per_page, token.next_page)
+            .await
+    }
+
+    // Stream every matching repository, fetching further pages as the consumer pulls items
+    pub fn search_repositories_stream<'a>(
+        &'a self,
+        cache: &'a Cache,
+        query: &'a str,
+        per_page: impl Into<Option<u32>>,
+    ) -> impl Stream<Item = Result<Repo, Error>> + 'a {
+        let pp = per_page.into().unwrap_or(10).min(100);
+
+        stream::unfold(1u32, move |page| async move {
+            // Stop once we would read past the 1000-result search cap
+            if (page - 1).saturating_mul(pp) >= SEARCH_RESULT_CAP {
+                return None;
+            }
+
+            // Make sure we still have quota before fetching another page
+            if page > 1
+                && let Err(err) = self.check_rate_limit().await
+            {
+                return Some((vec![Err(err)], u32::MAX));
+            }
+
+            match self
+                .search_repositories(cache, query, pp, page, None, None)
+                .await
+            {
+                Ok(response) if response.items.is_empty() => None, // No more results
+                Ok(response) => {
+                    let items: Vec<_> = response.items.into_iter().map(Ok).collect();
+                    Some((items, page + 1))
+                }
+                // Yield the error once, then end the stream
+                Err(err) => Some((vec![Err(err)], u32::MAX)),
+            }
+        })
+        .flat_map(stream::iter)
+    }
+
+    // Like `search_repositories_stream`, but skips repositories already
+    // yielded. Star-sorted results shift between page fetches as counts
+    // change, so the same repo can otherwise appear twice in one crawl.
+    pub fn search_repositories_stream_deduped<'a>(
+        &'a self,
+        cache: &'a Cache,
+        query: &'a str,
+        per_page: impl Into<Option<u32>>,
+    ) -> impl Stream<Item = Result<Repo, Error>> + 'a {
+        let mut seen = std::collections::HashSet::new();
+        self.search_repositories_stream(cache, query, per_page)
+            .filter(move |item| {
+                let keep = match item {
+                    Ok(repo) => seen.insert(repo.full_name.clone()),
+                    Err(_) => true, // Errors always pass through
+                };
+                futures::future::ready(keep)
+            })
+    }
+
+    // Stream at most `max_results` repositories. Pages are fetched lazily,
+    // so capping the stream also caps the quota spent: once enough items
+    // have been yielded no further page request is made. The 1000-result
+    // API cap still applies when `max_results` is larger than it.
+    pub fn search_repositories_stream_capped<'a>(
+        &'a self,
+        cache: &'a Cache,
+        query: &'a str,
+        per_page: impl Into<Option<u32>>,
+        max_results: usize,
+    ) -> impl Stream<Item = Result<Repo, Error>> + 'a {
+        self.search_repositories_stream(cache, query, per_page)
+            .take(max_results)
+    }
+
+    // The quick-script convenience: drive the pagination internally and hand
+    // back everything in one Vec, up to `max_results` items (and always within
+    // the 1000-result API cap). Stops at the first error.
+    pub async fn search_all_repositories(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        max_results: usize,
+    ) -> Result<Vec<Repo>, Error> {
+        self.search_repositories_stream_capped(cache, query, per_page, max_results)
+            .try_collect()
+            .await
+    }
+
+    // Fetch the full repository object for a search hit, e.g. "rust-lang/rust".
+    // Goes through the cache under a `repo-` key, revalidating by ETag like
+    // the search endpoints do.
+    pub async fn get_repository(
+        &self,
+        cache: &Cache,
+        full_name: &str,
+    ) -> Result<RepositoryDetails, Error> {
+        let cache_key = format!("repo-{}", full_name);
+
+        let request = self.request(&format!("/repos/{}", full_name));
+
+        self.cached_search(cache, &cache_key, request).await
+    }
+
+    // Fetch the full text of a code-search hit via its contents-API URL.
+    // GitHub stops inlining base64 content for large files (around 1 MB);
+    // those come back with a different encoding and get a clear error here.
+    pub async fn fetch_code_content(&self, file: &CodeSearchFile) -> Result<String, Error> {
+        // `file.url` is absolute, so this bypasses the base-URL join
+        let request = self.request_absolute(&file.url);
+
+        let fetched = self.fetch_search(request).await?;
+        let raw_body = fetched
+            .data
+            .ok_or_else(|| Error::Other("Got 304 Not Modified without a cached entry".to_string()))?;
+
+        // The contents API wraps the blob in an envelope with its encoding
+        #[derive(serde::Deserialize)]
+        struct BlobContent {
+            content: Option<String>,
+            encoding: String,
+            size: u64,
+        }
+
+        let blob: BlobContent = serde_json::from_slice(&raw_body)
+            .map_err(|e| Error::Other(format!("Failed to parse blob response: {}", e)))?;
+
+        if blob.encoding != "base64" {
+            return Err(Error::Other(format!(
+                "Cannot inline {} ({} bytes): encoding is '{}', likely over GitHub's size limit",
+                file.path, blob.size, blob.encoding
+            )));
+        }
+        let content = blob
+            .content
+            .ok_or_else(|| Error::Other(format!("Blob response for {} had no content", file.path)))?;
+
+        // The base64 payload is wrapped with newlines, which must go first
+        use base64::Engine as _;
+        let stripped: String = content.chars().filter(|c| !c.is_whitespace()).collect();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(stripped)
+            .map_err(|e| Error::Other(format!("Invalid base64 in blob response: {}", e)))?;
+
+        String::from_utf8(bytes)
+            .map_err(|e| Error::Other(format!("File {} is not valid UTF-8: {}", file.path, e)))
+    }
+
+    // The rate-limit bucket that actually governs search calls. The core
+    // `rate` bucket can show plenty of quota while search is already
+    // throttled, so check this one before a burst of searches.
+    pub async fn check_search_rate_limit(&self) -> Result<RateLimitInfo, Error> {
+        let request = self.request("/rate_limit").build()?;
+        let response = self.backend.execute(request).await?;
+        let response: RateLimit = serde_json::from_slice(&response.body)
+            .map_err(|e| Error::Other(format!("Failed to parse response: {}", e)))?;
+
+        let search = response
+            .resources
+            .map(|resources| resources.search)
+            .ok_or_else(|| Error::Other("Rate limit response had no search bucket".to_string()))?;
+
+        if search.remaining < 1 {
+            return Err(Error::RateLimited {
+                remaining: search.remaining,
+                limit: search.limit,
+                reset: search.reset,
+            });
+        }
+
+        Ok(search)
+    }
+
+    #[tracing::instrument(skip(self), fields(endpoint = "/rate_limit"))]
+    pub async fn check_rate_limit(&self) -> Result<RateLimit, Error> {
+        // Make the request to the rate limit endpoint
+        let request = self.request("/rate_limit").build()?;
+        let response = self.backend.execute(request).await?;
+        let response: RateLimit = serde_json::from_slice(&response.body) // Deserialize JSON into `RateLimit`
+            .map_err(|e| Error::Other(format!("Failed to parse response: {}", e)))?;
+
+        if response.rate.remaining < 1 {
+            return Err(Error::RateLimited {
+                remaining: response.rate.remaining,
+                limit: response.rate.limit,
+                reset: response.rate.reset,
+            });
+        }
+
+        Ok(response)
+    }
+}
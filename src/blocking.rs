@@ -0,0 +1,170 @@
+use crate::cache::{Cache, CachedResponse};
+use crate::errors::Error;
+use crate::models::{CodeSearchResponse, RateLimit, SearchResponse};
+use reqwest::blocking::Client;
+use tracing::debug;
+
+// Mirrors the async `GithubClient` for consumers that don't run tokio
+pub struct BlockingGithubClient {
+    http: Client,
+    base_url: String,
+}
+
+// Build a blocking reqwest client with the same headers as the async one
+fn build_http(token: Option<&str>, user_agent: &str) -> Result<Client, Error> {
+    let mut headers = reqwest::header::HeaderMap::new();
+
+    if let Some(token) = token {
+        headers.insert(
+            reqwest::header::AUTHORIZATION,
+            format!("Bearer {}", token)
+                .parse()
+                .map_err(|e| Error::Other(format!("Token is not a valid header value: {}", e)))?,
+        );
+    }
+    headers.insert(
+        reqwest::header::USER_AGENT,
+        user_agent
+            .parse()
+            .map_err(|e| Error::Other(format!("Invalid User-Agent: {}", e)))?,
+    );
+
+    Ok(Client::builder().default_headers(headers).build()?)
+}
+
+impl BlockingGithubClient {
+    // Build an authenticated client pointed at the public GitHub API
+    pub fn new(token: &str) -> Result<Self, Error> {
+        Ok(Self {
+            http: build_http(Some(token), "github_search_tool")?,
+            base_url: "https://api.github.com".to_owned(),
+        })
+    }
+
+    // Build an unauthenticated client for public searches at the lower rate limit
+    pub fn anonymous() -> Result<Self, Error> {
+        Ok(Self {
+            http: build_http(None, "github_search_tool")?,
+            base_url: "https://api.github.com".to_owned(),
+        })
+    }
+
+    // Join an endpoint path onto the configured base URL
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    pub fn search_repositories(
+        &self,
+        cache: &Cache,
+        query: &str,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<SearchResponse, Error> {
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+        let cache_key = format!("{}-{}-{}", query, pp, pg);
+
+        if let Some(CachedResponse::Search(cached_response)) = cache.blocking_get(&cache_key) {
+            debug!("Cache hit for query: {}", cache_key);
+            return Ok(cached_response);
+        }
+
+        debug!("Cache miss for query: {}", query);
+
+        let response = self
+            .http
+            .get(self.url("/search/repositories"))
+            .query(&[("q", query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)])
+            .send()?;
+
+        let status_code = response.status();
+        let raw_body = response.text()?;
+        check_status(status_code, &raw_body)?;
+
+        let result: SearchResponse = serde_json::from_str(&raw_body)
+            .map_err(|e| Error::Other(format!("Failed to parse response: {} — body: {}", e, raw_body)))?;
+
+        cache.blocking_insert(&cache_key, CachedResponse::Search(result.clone()));
+
+        Ok(result)
+    }
+
+    pub fn search_code(
+        &self,
+        cache: &Cache,
+        query: &str,
+        filename: Option<&str>,
+        per_page: impl Into<Option<u32>>,
+        page: impl Into<Option<u32>>,
+    ) -> Result<CodeSearchResponse, Error> {
+        let mut full_query = query.to_string();
+        if let Some(fname) = filename {
+            full_query.push_str(&format!(" filename:{}", fname));
+        }
+
+        let pp = per_page.into().unwrap_or(10).min(100);
+        let pg = page.into().unwrap_or(1);
+        let cache_key = format!("code-{}-{}-{}", full_query, pp, pg);
+
+        if let Some(CachedResponse::Code(cached_response)) = cache.blocking_get(&cache_key) {
+            debug!("Cache hit for code search query: {}", cache_key);
+            return Ok(cached_response);
+        }
+
+        debug!("Cache miss for code search query: {}", cache_key);
+
+        let response = self
+            .http
+            .get(self.url("/search/code"))
+            .query(&[("q", &full_query)])
+            .query(&[("per_page", pp)])
+            .query(&[("page", pg)])
+            .send()?;
+
+        let status_code = response.status();
+        let raw_body = response.text()?;
+        check_status(status_code, &raw_body)?;
+
+        let result: CodeSearchResponse = serde_json::from_str(&raw_body)
+            .map_err(|e| Error::Other(format!("Failed to parse response: {} — body: {}", e, raw_body)))?;
+
+        cache.blocking_insert(&cache_key, CachedResponse::Code(result.clone()));
+
+        Ok(result)
+    }
+
+    pub fn check_rate_limit(&self) -> Result<RateLimit, Error> {
+        let response = self
+            .http
+            .get(self.url("/rate_limit"))
+            .send()?
+            .json::<RateLimit>()?;
+
+        if response.rate.remaining < 1 {
+            return Err(Error::RateLimited {
+                remaining: response.rate.remaining,
+                limit: response.rate.limit,
+                reset: response.rate.reset,
+            });
+        }
+
+        Ok(response)
+    }
+}
+
+// Map non-success statuses to the same errors the async client produces
+fn check_status(status_code: reqwest::StatusCode, raw_body: &str) -> Result<(), Error> {
+    if status_code.eq(&403) {
+        Err(Error::Forbidden(raw_body.to_owned()))
+    } else if status_code.is_client_error() || status_code.is_server_error() {
+        Err(Error::ApiError {
+            status: status_code,
+            body: raw_body.to_owned(),
+        })
+    } else {
+        Ok(())
+    }
+}
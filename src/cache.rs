@@ -1,35 +1,225 @@
-use std::collections::HashMap;
-use std::sync::Mutex;
+use std::num::NonZeroUsize;
+use std::time::{Duration, Instant};
 
-use crate::models::{CodeSearchResponse, SearchResponse}; // Import your SearchResponse struct
+use lru::LruCache;
+use tokio::sync::RwLock;
+
+use crate::models::{
+    CodeSearchResponse, CommitSearchResponse, IssueSearchResponse, RepositoryDetails,
+    SearchResponse, TopicSearchResponse,
+}; // Import your SearchResponse struct
 
 #[derive(Clone, Debug)]
 pub enum CachedResponse {
     Search(SearchResponse), // For `search_repositories`
     Code(CodeSearchResponse), // For `search_code`
+    Issues(IssueSearchResponse), // For `search_issues`
+    Commits(CommitSearchResponse), // For `search_commits`
+    Repository(Box<RepositoryDetails>), // For `get_repository`; boxed to keep the enum small
+    Topics(TopicSearchResponse), // For `search_topics`
+    // A remembered 422 for a malformed query, so resubmitting it can replay
+    // the error instead of spending another request; expires with the TTL
+    Invalid { status: u16, body: String },
+}
+
+// A cached value together with when it was stored, so it can expire,
+// and the ETag GitHub sent with it, so it can be revalidated cheaply
+struct CacheEntry {
+    response: CachedResponse,
+    inserted_at: Instant,
+    etag: Option<String>,
 }
 
 pub struct Cache {
-    data: Mutex<HashMap<String, CachedResponse>>, // A thread-safe cache
+    data: RwLock<LruCache<String, CacheEntry>>, // A thread-safe cache
+    ttl: Option<Duration>, // How long entries stay valid; `None` keeps them forever
 }
 
 impl Cache {
-    // Initialize a new cache
-    pub fn new() -> Self {
+    // Initialize a new cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            data: RwLock::new(LruCache::unbounded()),
+            ttl: Some(ttl),
+        }
+    }
+
+    // Initialize a cache that keeps entries forever (the old behavior)
+    pub fn new_unbounded() -> Self {
         Self {
-            data: Mutex::new(HashMap::new()),
+            data: RwLock::new(LruCache::unbounded()),
+            ttl: None,
         }
     }
 
-    // Check the cache for a query
-    pub fn get(&self, query: &str) -> Option<CachedResponse> {
-        let cache = self.data.lock().unwrap(); // Access the cache
-        cache.get(query).cloned() // Clone the value if it exists (to avoid borrowing issues)
+    // Initialize a cache that holds at most `max_entries`, evicting the
+    // least-recently-used entry when it fills up
+    pub fn with_capacity(max_entries: usize) -> Self {
+        let capacity = NonZeroUsize::new(max_entries).expect("cache capacity must be non-zero");
+        Self {
+            data: RwLock::new(LruCache::new(capacity)),
+            ttl: None,
+        }
+    }
+
+    // Check the cache for a query; expired entries count as a miss but are
+    // kept around so they can be revalidated via `get_stale`
+    pub async fn get(&self, query: &str) -> Option<CachedResponse> {
+        // A write lock is needed even on reads: the LRU order is updated
+        let mut cache = self.data.write().await;
+
+        if let Some(ttl) = self.ttl
+            && let Some(entry) = cache.peek(query)
+            && entry.inserted_at.elapsed() > ttl
+        {
+            return None; // Too old: treat as a miss
+        }
+
+        cache.get(query).map(|entry| entry.response.clone()) // Clone the value if it exists (to avoid borrowing issues)
+    }
+
+    // Fetch an entry regardless of its age, along with its ETag, so the
+    // caller can ask GitHub whether it is still current
+    pub async fn get_stale(&self, query: &str) -> Option<(CachedResponse, Option<String>)> {
+        let mut cache = self.data.write().await;
+        cache
+            .get(query)
+            .map(|entry| (entry.response.clone(), entry.etag.clone()))
+    }
+
+    // Mark an entry as fresh again after GitHub confirmed it is unchanged
+    pub async fn touch(&self, query: &str) {
+        let mut cache = self.data.write().await;
+        if let Some(entry) = cache.get_mut(query) {
+            entry.inserted_at = Instant::now();
+        }
+    }
+
+    // Synchronous accessors for the `blocking` API, which has no runtime to await on
+    #[cfg(feature = "blocking")]
+    pub fn blocking_get(&self, query: &str) -> Option<CachedResponse> {
+        let mut cache = self.data.blocking_write();
+
+        if let Some(ttl) = self.ttl
+            && let Some(entry) = cache.peek(query)
+            && entry.inserted_at.elapsed() > ttl
+        {
+            return None; // Too old: treat as a miss
+        }
+
+        cache.get(query).map(|entry| entry.response.clone())
+    }
+
+    #[cfg(feature = "blocking")]
+    pub fn blocking_insert(&self, query: &str, response: CachedResponse) {
+        let mut cache = self.data.blocking_write();
+        let entry = CacheEntry {
+            response,
+            inserted_at: Instant::now(),
+            etag: None,
+        };
+        cache.put(query.to_string(), entry);
+    }
+
+    // Drop every cached entry
+    pub async fn clear(&self) {
+        let mut cache = self.data.write().await;
+        cache.clear();
+    }
+
+    // Remove a single entry, forcing the next lookup to refetch
+    pub async fn invalidate(&self, query: &str) {
+        let mut cache = self.data.write().await;
+        cache.pop(query);
+    }
+
+    // Remove every entry whose key starts with `prefix`, e.g. `"code-"`
+    pub async fn invalidate_prefix(&self, prefix: &str) {
+        let mut cache = self.data.write().await;
+        let keys: Vec<String> = cache
+            .iter()
+            .filter(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in keys {
+            cache.pop(&key);
+        }
     }
 
     // Insert a result into the cache
-    pub fn insert(&self, query: &str, response: CachedResponse) {
-        let mut cache = self.data.lock().unwrap(); // Access the cache
-        cache.insert(query.to_string(), response); // Insert the query and its response
+    pub async fn insert(&self, query: &str, response: CachedResponse) {
+        self.insert_with_etag(query, response, None).await;
+    }
+
+    // Insert a result along with the ETag GitHub sent for it
+    pub async fn insert_with_etag(&self, query: &str, response: CachedResponse, etag: Option<String>) {
+        let mut cache = self.data.write().await;
+        let entry = CacheEntry {
+            response,
+            inserted_at: Instant::now(),
+            etag,
+        };
+        cache.put(query.to_string(), entry); // Insert the query and its response
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SearchResponse;
+
+    fn sample_response() -> CachedResponse {
+        CachedResponse::Search(SearchResponse {
+            total_count: 1,
+            incomplete_results: false,
+            items: Vec::new(),
+        })
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_the_ttl() {
+        let cache = Cache::new(Duration::from_millis(50));
+        cache.insert("rust", sample_response()).await;
+        assert!(cache.get("rust").await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(cache.get("rust").await.is_none());
     }
-}
\ No newline at end of file
+
+    #[tokio::test]
+    async fn capacity_overflow_evicts_the_least_recently_used_entry() {
+        let cache = Cache::with_capacity(2);
+        cache.insert("a", sample_response()).await;
+        cache.insert("b", sample_response()).await;
+
+        // Touch "a" so "b" becomes the least recently used
+        assert!(cache.get("a").await.is_some());
+
+        cache.insert("c", sample_response()).await; // Should push out "b"
+        assert!(cache.get("b").await.is_none());
+        assert!(cache.get("a").await.is_some());
+        assert!(cache.get("c").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn invalidate_prefix_only_drops_matching_keys() {
+        let cache = Cache::new_unbounded();
+        cache.insert("code-rust", sample_response()).await;
+        cache.insert("code-go", sample_response()).await;
+        cache.insert("issues-rust", sample_response()).await;
+
+        cache.invalidate_prefix("code-").await;
+        assert!(cache.get("code-rust").await.is_none());
+        assert!(cache.get("code-go").await.is_none());
+        assert!(cache.get("issues-rust").await.is_some());
+    }
+
+    #[tokio::test]
+    async fn unbounded_entries_never_expire() {
+        let cache = Cache::new_unbounded();
+        cache.insert("rust", sample_response()).await;
+
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(cache.get("rust").await.is_some());
+    }
+}
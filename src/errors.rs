@@ -1,8 +1,80 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use thiserror::Error;
 use reqwest::Error as ReqwestError;
+use reqwest::StatusCode;
 
+#[derive(Error, Debug)]
 pub enum Error {
+    // The underlying HTTP request failed before we got a response
+    #[error("request failed: {0}")]
     Reqwest(ReqwestError),
+
+    // The request exceeded the configured timeout
+    #[error("request timed out")]
+    Timeout,
+
+    // GitHub refused the request (403)
+    #[error("permission denied: {0}")]
     Forbidden(String),
+
+    // The API quota is exhausted; `reset` is a Unix timestamp
+    #[error("rate limited: {remaining} of {limit} requests remaining, resets at {reset}")]
+    RateLimited { remaining: u32, limit: u32, reset: u64 },
+
+    // GitHub's abuse-detection (secondary) rate limit, distinct from quota
+    // exhaustion: back off for at least a minute before retrying
+    #[error("secondary rate limit hit; retry after {retry_after:?}")]
+    SecondaryRateLimited { retry_after: Option<Duration> },
+
+    // GitHub answered with an error status; the body carries its explanation.
+    // Matching on `status` distinguishes e.g. a 422 (bad query) from a 401.
+    #[error("GitHub API error {status}: {body}")]
+    ApiError { status: StatusCode, body: String },
+
+    // The requested page lies beyond GitHub's hard cap of 1000 search results
+    #[error("GitHub search only exposes the first 1000 results; requested page is out of reach")]
+    ResultLimitReached,
+
+    // The response body grew past the configured size cap and reading was
+    // aborted before buffering the rest
+    #[error("response body exceeded the configured limit of {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+
+    // GitHub timed out internally and returned a truncated result set; only
+    // surfaced when the client was built in strict mode
+    #[error("GitHub returned incomplete results (search timed out server-side)")]
+    IncompleteResults,
+
+    // The caller cancelled the operation via a `CancellationToken`
+    #[error("operation cancelled")]
+    Cancelled,
+
+    // Anything else that went wrong, with a human-readable description
+    #[error("{0}")]
     Other(String),
-}
\ No newline at end of file
+}
+
+// Surface timeouts as their own variant so callers can retry them specifically
+impl From<ReqwestError> for Error {
+    fn from(err: ReqwestError) -> Self {
+        if err.is_timeout() {
+            Error::Timeout
+        } else {
+            Error::Reqwest(err)
+        }
+    }
+}
+
+impl Error {
+    // How long until the rate-limit window resets, for `RateLimited` errors
+    pub fn reset_duration(&self) -> Option<Duration> {
+        match self {
+            Error::RateLimited { reset, .. } => {
+                let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+                Some(Duration::from_secs(reset.saturating_sub(now)))
+            }
+            _ => None,
+        }
+    }
+}
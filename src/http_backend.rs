@@ -0,0 +1,87 @@
+use futures::future::BoxFuture;
+use futures::StreamExt;
+
+use crate::errors::Error;
+
+// How much response body to buffer before giving up, when no explicit limit
+// is configured. Generous enough for any legitimate search page.
+pub(crate) const DEFAULT_MAX_RESPONSE_BYTES: usize = 50 * 1024 * 1024;
+
+// A fully-buffered HTTP response: everything the client needs to interpret
+// what GitHub sent back, detached from any particular HTTP library
+pub struct HttpResponse {
+    pub status: reqwest::StatusCode,
+    pub headers: reqwest::header::HeaderMap,
+    pub body: bytes::Bytes,
+}
+
+// The seam between the client and the network. `reqwest::Client` implements
+// it for real traffic; tests can inject a fake that serves canned responses
+// without touching the API (see `crate::testing::MockBackend`).
+pub trait HttpBackend: Send + Sync {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse, Error>>;
+}
+
+// Buffer a response body, aborting as soon as it exceeds `limit` rather than
+// trusting the server not to send something enormous
+async fn read_limited(response: reqwest::Response, limit: usize) -> Result<HttpResponse, Error> {
+    let status = response.status();
+    let headers = response.headers().clone();
+
+    // A declared Content-Length over the limit saves reading anything at all
+    if let Some(length) = response.content_length()
+        && length as usize > limit
+    {
+        return Err(Error::ResponseTooLarge { limit });
+    }
+
+    let mut body = Vec::new();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk?;
+        if body.len() + chunk.len() > limit {
+            return Err(Error::ResponseTooLarge { limit });
+        }
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok(HttpResponse {
+        status,
+        headers,
+        body: bytes::Bytes::from(body),
+    })
+}
+
+// The backend `GithubClient` uses for real traffic: a reqwest client plus
+// the client's configured response-size cap
+pub struct ReqwestBackend {
+    http: reqwest::Client,
+    max_response_bytes: usize,
+}
+
+impl ReqwestBackend {
+    pub fn new(http: reqwest::Client, max_response_bytes: usize) -> Self {
+        Self {
+            http,
+            max_response_bytes,
+        }
+    }
+}
+
+impl HttpBackend for ReqwestBackend {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let response = self.http.execute(request).await?;
+            read_limited(response, self.max_response_bytes).await
+        })
+    }
+}
+
+impl HttpBackend for reqwest::Client {
+    fn execute(&self, request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            let response = reqwest::Client::execute(self, request).await?;
+            read_limited(response, DEFAULT_MAX_RESPONSE_BYTES).await
+        })
+    }
+}
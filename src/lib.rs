@@ -0,0 +1,29 @@
+#[cfg(feature = "async")]
+pub mod api_client;
+#[cfg(feature = "async")]
+pub mod http_backend;
+#[cfg(feature = "async")]
+pub mod testing;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod cache;
+pub mod errors;
+pub mod models;
+pub mod search_query;
+
+// Re-export the types most callers need directly
+#[cfg(feature = "async")]
+pub use api_client::{CancellationToken, GithubClient};
+#[cfg(feature = "blocking")]
+pub use blocking::BlockingGithubClient;
+pub use cache::{Cache, CachedResponse};
+pub use errors::Error;
+#[cfg(feature = "async")]
+pub use http_backend::{HttpBackend, HttpResponse, ReqwestBackend};
+pub use models::{
+    CodeSearchFile, CodeSearchResponse, CombinedResult, Commit, CommitSearchResponse, Issue, IssueSearchResponse,
+    LenientSearchResponse, MinimalSearchResponse, Paginated, RateLimit, RateLimitResources,
+    Repo, RepoMinimal, RepositoryDetails, ResumeToken, SearchResponse, Topic,
+    TopicSearchResponse,
+};
+pub use search_query::{CodeSearchQuery, Comparison, GithubSearchQuery, SearchField, UserSearchQuery, UserType, Visibility};
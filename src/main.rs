@@ -1,81 +1,200 @@
-mod models;
-mod search_query;
-mod api_client;
-mod errors;
-mod cache;
-
+use clap::{Parser, ValueEnum};
 use dotenv::dotenv;
+use github_search::{Cache, GithubClient, GithubSearchQuery};
 use std::env;
-use reqwest::Client;
-use api_client::search_repositories;
-use crate::api_client::check_rate_limit;
-use crate::search_query::GithubSearchQuery;
+
+/// Search GitHub repositories and code from the command line
+#[derive(Parser)]
+struct Args {
+    /// The search term
+    term: String,
+
+    /// Only match repositories in this language
+    #[arg(long)]
+    language: Option<String>,
+
+    /// Require at least this many stars
+    #[arg(long)]
+    min_stars: Option<u32>,
+
+    /// Only match repositories with this topic
+    #[arg(long)]
+    topic: Option<String>,
+
+    /// Number of results per page (max 100)
+    #[arg(long, default_value_t = 10)]
+    per_page: u32,
+
+    /// Which page of results to fetch
+    #[arg(long, default_value_t = 1)]
+    page: u32,
+
+    /// Sort by stars, forks, or updated instead of best match
+    #[arg(long)]
+    sort: Option<String>,
+
+    /// Search code instead of repositories
+    #[arg(long)]
+    code: bool,
+
+    /// How to print repository results
+    #[arg(long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Skip the up-front rate-limit check (saves a request and a round trip)
+    #[arg(long)]
+    skip_rate_limit_check: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutputFormat {
+    /// Dump the full response as pretty-printed JSON
+    Json,
+    /// full_name,stars,language,url columns
+    Csv,
+    /// An aligned text grid
+    Table,
+}
 
 #[tokio::main] // Marks the main function as asynchronous
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     dotenv().ok();
-    let token = env::var("GITHUB_TOKEN").expect("Expected a GITHUB_TOKEN in the environment");
-
-    // Create a new HTTP client with the Authorization header
-    let client = Client::builder()
-        .default_headers({
-            let mut headers = reqwest::header::HeaderMap::new();
-            headers.insert(
-                reqwest::header::AUTHORIZATION,
-                format!("Bearer {}", token).parse().unwrap(),
-            );
-            headers.insert(
-                reqwest::header::USER_AGENT,
-                "LeapTheory-Test-App/1.0".parse().unwrap(),
-            );
-            headers
-        })
-        .build()?;
-
-    let cache = cache::Cache::new(); // Initialize a new in-memory cache
-
-    match check_rate_limit(&client).await {
-        Ok(limit) => {
-            println!("{} requests remaining", limit.rate.remaining);
-        },
-        Err(err) => {
-            println!("Rate limit error: {}", err.to_string());
+    tracing_subscriber::fmt::init(); // Let RUST_LOG control library log verbosity
+    let args = Args::parse();
+    // An unset or empty token gets a friendly message instead of a panic backtrace
+    let token = match env::var("GITHUB_TOKEN") {
+        Ok(token) if !token.trim().is_empty() => token,
+        _ => {
+            eprintln!("No GitHub token found. Set GITHUB_TOKEN in your environment or .env file.");
             std::process::exit(1);
         }
+    };
+
+    // Create an authenticated client; the library sets the auth and User-Agent headers
+    let client = GithubClient::with_user_agent(&token, "LeapTheory-Test-App/1.0")?;
+
+    let cache = Cache::new(std::time::Duration::from_secs(300)); // In-memory cache with a 5 minute TTL
+
+    // The precheck costs a request and a round trip of its own; with
+    // --skip-rate-limit-check we rely on the X-RateLimit-* headers the
+    // search response itself carries (printed after the search below)
+    if !args.skip_rate_limit_check {
+        match client.check_rate_limit().await {
+            Ok(limit) => {
+                println!("{} requests remaining", limit.rate.remaining);
+            },
+            Err(err) => {
+                println!("Rate limit error: {}", err);
+                std::process::exit(1);
+            }
+        }
     }
 
-    let query = GithubSearchQuery::new("rust async")
-        .language("rust")
-        .min_stars("5000")
-        .to_query_string();
+    // Build the query from the command-line filters
+    let mut query = GithubSearchQuery::new(&args.term);
+    if let Some(language) = &args.language {
+        query = query.language(language);
+    }
+    if let Some(min_stars) = args.min_stars {
+        query = query.min_stars(min_stars);
+    }
+    if let Some(topic) = &args.topic {
+        query = query.topic(topic);
+    }
+    let query = query.to_query_string();
+
+    if args.code {
+        // Code search mode
+        match client
+            .search_code(&cache, &query, None, args.per_page, args.page)
+            .await
+        {
+            Ok(response) => {
+                println!("Found {} files:", response.total_count);
+                for file in response.items {
+                    println!("- {} ({})", file.path, file.repository.full_name);
+                }
+            },
+            Err(err) => {
+                eprintln!("Error while searching: {}", err);
+            },
+        }
+        if args.skip_rate_limit_check
+            && let Some(info) = client.last_rate_limit()
+        {
+            println!("{} requests remaining", info.remaining);
+        }
+        return Ok(());
+    }
 
     // Send the search request
-    match search_repositories(&client, &cache, &query, Some(&1)).await {
+    match client
+        .search_repositories(
+            &cache,
+            &query,
+            args.per_page,
+            args.page,
+            args.sort.as_deref(),
+            None,
+        )
+        .await
+    {
         Ok(response) => {
-            println!("Found {} repositories:", response.total_count);
-            for repo in response.items {
-                println!("- {} ({} stars)", repo.full_name, repo.stargazers_count);
-            }
+            print_repositories(&response, args.format)?;
         },
         Err(err) => {
             eprintln!("Error while searching: {}", err);
         },
     }
 
-    // Re-use cache for the same query
-    println!("Re-running the same query to check caching...");
+    if args.skip_rate_limit_check
+        && let Some(info) = client.last_rate_limit()
+    {
+        println!("{} requests remaining", info.remaining);
+    }
+
+    Ok(())
+}
 
-    match search_repositories(&client, &cache, &query, Some(&1)).await {
-        Ok(response) => {
-            println!("Cache response: Found {} repositories:", response.total_count);
-            for repo in response.items {
-                println!("- {} ({} stars)", repo.full_name, repo.stargazers_count);
+// Render repository results in the requested output format
+fn print_repositories(
+    response: &github_search::SearchResponse,
+    format: OutputFormat,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match format {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(response)?);
+        }
+        OutputFormat::Csv => {
+            println!("full_name,stars,language,url");
+            for repo in &response.items {
+                println!(
+                    "{},{},{},{}",
+                    repo.full_name,
+                    repo.stargazers_count,
+                    repo.language.as_deref().unwrap_or(""),
+                    repo.html_url
+                );
             }
         }
-        Err(err) => {
-            eprintln!("Error while searching: {}", err);
+        OutputFormat::Table => {
+            println!("Found {} repositories:", response.total_count);
+            // Size the name column to the longest entry so the grid lines up
+            let name_width = response
+                .items
+                .iter()
+                .map(|repo| repo.full_name.len())
+                .max()
+                .unwrap_or(0);
+            for repo in &response.items {
+                println!(
+                    "{:name_width$}  {:>8}  {}",
+                    repo.full_name,
+                    repo.stargazers_count,
+                    repo.language.as_deref().unwrap_or("-"),
+                );
+            }
         }
     }
-
     Ok(())
 }
@@ -1,4 +1,20 @@
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// One highlighted position within a text-match fragment
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TextMatchEntry {
+    pub text: String,       // The matched text itself
+    pub indices: Vec<u32>,  // Start and end offsets within the fragment
+}
+
+// A matched fragment returned when text-match highlighting is requested
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TextMatch {
+    pub fragment: String,             // The snippet surrounding the match
+    pub property: Option<String>,     // Which field matched (e.g. "content")
+    pub matches: Vec<TextMatchEntry>, // Where the term occurs inside the fragment
+}
 
 #[derive(serde::Deserialize, Debug, Clone)]
 pub struct CodeSearchFile {
@@ -9,6 +25,9 @@ pub struct CodeSearchFile {
     pub git_url: String,
     pub html_url: String,
     pub repository: Repository, // Related repository details
+    pub text_matches: Option<Vec<TextMatch>>, // Present only when highlighting was requested
+    #[serde(default)]
+    pub score: f64, // Relevance ranking assigned by GitHub search
 }
 
 #[derive(serde::Deserialize, Debug, Clone)]
@@ -25,28 +44,270 @@ pub struct Repository {
     pub html_url: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RepoOwner {
+    pub login: String, // The user or organization owning the repo
+    pub avatar_url: Option<String>, // Their avatar image, for rendering in UIs
+    pub html_url: Option<String>,   // Their profile page
+    #[serde(rename = "type")]
+    pub owner_type: Option<String>, // "User" or "Organization"
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct Repo {
     pub full_name: String,         // e.g., "rust-lang/rust"
     pub description: Option<String>, // Optional: Not all repos have a description
     pub stargazers_count: u32,     // Number of stars
     pub language: Option<String>, // Primary language
     pub html_url: String,          // Link to repo
+    pub forks_count: Option<u32>,       // Number of forks
+    pub open_issues_count: Option<u32>, // Open issues and pull requests
+    pub watchers_count: Option<u32>,    // Number of watchers
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub default_branch: Option<String>, // Usually "main" or "master"
+    pub homepage: Option<String>,       // The project's website, when one is set
+    pub owner: Option<RepoOwner>,
+    #[serde(default)]
+    pub topics: Vec<String>, // Repository topics; empty when the API omits them
+    #[serde(default)]
+    pub score: f64, // Relevance ranking assigned by GitHub search
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SearchResponse {
     pub total_count: u32,          // Total number of matching repositories
     pub incomplete_results: bool, // If not all results are complete
     pub items: Vec<Repo>,         // A list of repositories
 }
 
+impl SearchResponse {
+    // GitHub sets `incomplete_results` when its own search timed out
+    // internally; when this returns false, consider retrying the query
+    // or narrowing it before trusting the result set
+    pub fn is_complete(&self) -> bool {
+        !self.incomplete_results
+    }
+
+    // How many pages of `per_page` results are actually reachable, taking
+    // GitHub's hard cap of 1000 search results into account
+    pub fn total_pages(&self, per_page: u32) -> u32 {
+        let reachable = self.total_count.min(1000);
+        reachable.div_ceil(per_page.max(1))
+    }
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct CommitAuthor {
+    pub name: String,
+    pub date: DateTime<Utc>, // When the commit was authored
+}
+
+// The nested `commit` object holding the message and author
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct CommitDetail {
+    pub message: String,
+    pub author: CommitAuthor,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Commit {
+    pub sha: String,
+    pub commit: CommitDetail,
+    pub html_url: String,       // Link to the commit
+    pub repository: Repository, // Related repository details
+    #[serde(default)]
+    pub score: f64, // Relevance ranking assigned by GitHub search
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct CommitSearchResponse {
+    pub total_count: u32,
+    pub incomplete_results: bool,
+    pub items: Vec<Commit>, // A list of matching commits
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct IssueUser {
+    pub login: String, // The user who opened the issue
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Issue {
+    pub title: String,
+    pub number: u64,           // Issue/PR number within its repository
+    pub state: String,         // "open" or "closed"
+    pub html_url: String,      // Link to the issue
+    pub user: IssueUser,       // Who opened it
+    pub created_at: DateTime<Utc>, // When it was opened
+    pub comments: u32,         // Number of comments
+    #[serde(default)]
+    pub score: f64, // Relevance ranking assigned by GitHub search
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct IssueSearchResponse {
+    pub total_count: u32,
+    pub incomplete_results: bool,
+    pub items: Vec<Issue>, // A list of matching issues and pull requests
+}
+
+// One topic from the `/search/topics` endpoint
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct Topic {
+    pub name: String,                      // The slug used in `topic:` qualifiers
+    pub display_name: Option<String>,      // Human-readable name, when curated
+    pub short_description: Option<String>, // One-line description, when curated
+    pub featured: bool,                    // Featured on github.com/topics
+    pub curated: bool,                     // Has curated metadata in github/explore
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct TopicSearchResponse {
+    pub total_count: u32,
+    pub incomplete_results: bool,
+    pub items: Vec<Topic>, // A list of matching topics
+}
+
+// A pared-down search hit for consumers that only need to link to results;
+// skipping the other fields makes 100-item pages noticeably cheaper to parse
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct RepoMinimal {
+    pub full_name: String,
+    pub html_url: String,
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
+pub struct MinimalSearchResponse {
+    pub total_count: u32,
+    pub incomplete_results: bool,
+    pub items: Vec<RepoMinimal>,
+}
+
+// The full repository object from `/repos/{owner}/{name}`, which carries
+// fields the search endpoint omits
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct RepositoryDetails {
+    pub name: String,
+    pub full_name: String,
+    pub description: Option<String>,
+    pub html_url: String,
+    pub homepage: Option<String>,
+    pub language: Option<String>,
+    pub stargazers_count: u32,
+    pub forks_count: u32,
+    pub open_issues_count: u32,
+    pub subscribers_count: Option<u32>, // True watcher count; absent in search results
+    pub size: u32,                      // Size in kilobytes
+    pub fork: bool,
+    pub archived: bool,
+    pub default_branch: String,
+    pub created_at: Option<DateTime<Utc>>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub owner: Option<RepoOwner>,
+    #[serde(default)]
+    pub topics: Vec<String>,
+}
+
+// One hit from a combined repository + code search, tagged with where it
+// came from (see `GithubClient::search_combined`)
+#[derive(Debug, Clone)]
+pub enum CombinedResult {
+    Repository(Repo),
+    Code(CodeSearchFile),
+}
+
+impl CombinedResult {
+    // The raw relevance score GitHub assigned within its own endpoint
+    pub fn score(&self) -> f64 {
+        match self {
+            CombinedResult::Repository(repo) => repo.score,
+            CombinedResult::Code(file) => file.score,
+        }
+    }
+}
+
+// The outcome of a lenient search: every item that parsed cleanly, plus a
+// count of the malformed entries that had to be skipped
+#[derive(Debug, Clone)]
+pub struct LenientSearchResponse {
+    pub response: SearchResponse,
+    pub skipped: u32, // How many items failed to deserialize
+}
+
+// A response body together with pagination info parsed from the Link header
+#[derive(Debug, Clone)]
+pub struct Paginated<T> {
+    pub data: T,
+    pub next_page: Option<u32>, // The page after the one just fetched, if any
+    pub last_page: Option<u32>, // The total page count, when GitHub reports it
+}
+
+impl<T> Paginated<T> {
+    pub fn has_next_page(&self) -> bool {
+        self.next_page.is_some()
+    }
+}
+
+// Where to pick an interrupted crawl back up: the query plus the next page.
+// Serializes to a stable `v1:<page>:<per_page>:<query>` string so tokens can
+// be persisted across runs and crate versions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResumeToken {
+    pub query: String,
+    pub per_page: u32,
+    pub next_page: u32,
+}
+
+impl std::fmt::Display for ResumeToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The query goes last so embedded colons cannot break parsing
+        write!(f, "v1:{}:{}:{}", self.next_page, self.per_page, self.query)
+    }
+}
+
+impl std::str::FromStr for ResumeToken {
+    type Err = crate::errors::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.splitn(4, ':');
+        let invalid = || crate::errors::Error::Other(format!("Invalid resume token: {}", s));
+
+        if parts.next() != Some("v1") {
+            return Err(invalid());
+        }
+        let next_page = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let per_page = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let query = parts.next().ok_or_else(invalid)?.to_string();
+
+        Ok(ResumeToken {
+            query,
+            per_page,
+            next_page,
+        })
+    }
+}
+
 #[derive(serde::Deserialize, Debug)]
 pub struct RateLimit {
     pub rate: RateLimitInfo, // General API rate limit info
+    #[serde(default)]
+    pub resources: Option<RateLimitResources>, // Per-category buckets, when present
 }
 
+// The per-category buckets from `/rate_limit`; search has its own, much
+// stricter limit (30/min) that the core `rate` bucket does not reflect
 #[derive(serde::Deserialize, Debug)]
+pub struct RateLimitResources {
+    pub core: RateLimitInfo,
+    pub search: RateLimitInfo,
+    #[serde(default)]
+    pub code_search: Option<RateLimitInfo>, // Only reported for some token types
+}
+
+#[derive(serde::Deserialize, Debug, Clone)]
 pub struct RateLimitInfo {
     pub limit: u32,        // Total allowable requests per interval
     pub remaining: u32,    // Remaining requests for the interval
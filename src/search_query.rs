@@ -1,8 +1,187 @@
+// Where the search term is allowed to match
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchField {
+    Name,
+    Description,
+    Readme,
+    Topics,
+}
+
+impl SearchField {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SearchField::Name => "name",
+            SearchField::Description => "description",
+            SearchField::Readme => "readme",
+            SearchField::Topics => "topics",
+        }
+    }
+}
+
+// Repository visibility, mapping to GitHub's `is:` qualifiers
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Visibility {
+    Public,
+    Private,
+    Internal,
+}
+
+impl Visibility {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Private => "private",
+            Visibility::Internal => "internal",
+        }
+    }
+}
+
+// The comparator part of a `key:value` qualifier, e.g. `stars:>=100`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+impl Comparison {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "",
+            Comparison::Gt => ">",
+            Comparison::Gte => ">=",
+            Comparison::Lt => "<",
+            Comparison::Lte => "<=",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub struct GithubSearchQuery {
     pub term: String,
-    pub language: Option<String>,
-    pub min_stars: Option<String>,
-    pub topic: Option<String>,
+    pub extra_terms: Vec<String>, // Further required terms; GitHub ANDs them implicitly
+    pub any_terms: Vec<String>,   // Alternative terms joined with OR
+    pub languages: Vec<String>,
+    pub min_stars: Option<u32>,
+    pub max_stars: Option<u32>,
+    pub min_forks: Option<String>,
+    pub max_forks: Option<String>,
+    pub min_size: Option<String>,
+    pub max_size: Option<String>,
+    pub min_good_first_issues: Option<u32>,
+    pub min_help_wanted_issues: Option<u32>,
+    pub topics: Vec<String>, // `topic:` qualifiers; multiple entries AND together
+    pub created_after: Option<String>,
+    pub created_before: Option<String>,
+    pub pushed_after: Option<String>,
+    pub pushed_before: Option<String>,
+    pub users: Vec<String>,
+    pub org: Option<String>,
+    pub repo: Option<String>,
+    pub fork: Option<String>,
+    pub archived: Option<bool>,
+    pub template: Option<bool>,   // `template:` qualifier
+    pub sponsorable: bool,        // `is:sponsorable`; needs an authenticated request
+    pub visibility: Option<Visibility>,
+    pub mirror: Option<bool>,
+    pub state: Option<String>,
+    pub labels: Vec<String>,
+    pub licenses: Vec<String>,
+    pub search_in: Vec<SearchField>,
+    pub excluded_terms: Vec<String>,
+    pub excluded_languages: Vec<String>,
+    pub qualifiers: Vec<(String, Comparison, String)>, // Free-form `key:op value` qualifiers
+}
+
+// Check that a date string is ISO-8601 (YYYY-MM-DD) before using it as a qualifier
+fn is_valid_date(date: &str) -> bool {
+    chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").is_ok()
+}
+
+// GitHub rejects search queries longer than this with a 422
+const MAX_QUERY_LENGTH: usize = 256;
+// ...and queries chaining more than this many AND/OR/NOT operators
+const MAX_QUERY_OPERATORS: usize = 5;
+
+// Check a rendered query against GitHub's documented limits, so malformed
+// queries fail with a clear message before spending a request on a 422
+pub(crate) fn validate_query(query: &str) -> Result<(), crate::errors::Error> {
+    let length = query.chars().count();
+    if length > MAX_QUERY_LENGTH {
+        return Err(crate::errors::Error::Other(format!(
+            "Query is {} characters long; GitHub rejects queries over {}",
+            length, MAX_QUERY_LENGTH
+        )));
+    }
+    let operators = query
+        .split_whitespace()
+        .filter(|token| matches!(*token, "AND" | "OR" | "NOT"))
+        .count();
+    if operators > MAX_QUERY_OPERATORS {
+        return Err(crate::errors::Error::Other(format!(
+            "Query uses {} AND/OR/NOT operators; GitHub allows at most {}",
+            operators, MAX_QUERY_OPERATORS
+        )));
+    }
+    Ok(())
+}
+
+// Canonicalize a query string for use as a cache key: qualifier keys are
+// lowercased and qualifiers are sorted, so reordered but semantically equal
+// queries share one cache entry. Quoted phrases are kept intact.
+// Only the async client builds cache keys this way, hence the feature gate.
+#[cfg(feature = "async")]
+pub(crate) fn normalize_query(query: &str) -> String {
+    // Split on spaces, but keep quoted phrases together
+    let mut tokens: Vec<String> = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    for ch in query.chars() {
+        match ch {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.push(ch);
+            }
+            ' ' if !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            _ => current.push(ch),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    // Bare terms keep their order (it affects relevance); qualifiers don't,
+    // so they are sorted into a canonical order after the terms
+    let mut terms = Vec::new();
+    let mut qualifiers = Vec::new();
+    for token in tokens {
+        match token.split_once(':') {
+            Some((key, value)) if !token.starts_with('"') => {
+                qualifiers.push(format!("{}:{}", key.to_lowercase(), value));
+            }
+            _ => terms.push(token),
+        }
+    }
+    qualifiers.sort();
+
+    terms.extend(qualifiers);
+    terms.join(" ")
+}
+
+// Wrap multi-word terms in quotes so GitHub treats them as an exact phrase
+// instead of separate words; already-quoted terms are left alone
+fn quote_term(term: &str) -> String {
+    if term.contains(char::is_whitespace) && !(term.starts_with('"') && term.ends_with('"')) {
+        format!("\"{}\"", term)
+    } else {
+        term.to_owned()
+    }
 }
 
 impl GithubSearchQuery {
@@ -10,42 +189,1053 @@ impl GithubSearchQuery {
     pub fn new(term: &str) -> Self {
         Self {
             term: term.to_owned(),
-            language: None,
+            extra_terms: Vec::new(),
+            any_terms: Vec::new(),
+            languages: Vec::new(),
             min_stars: None,
-            topic: None,
+            max_stars: None,
+            min_forks: None,
+            max_forks: None,
+            min_size: None,
+            max_size: None,
+            min_good_first_issues: None,
+            min_help_wanted_issues: None,
+            topics: Vec::new(),
+            created_after: None,
+            created_before: None,
+            pushed_after: None,
+            pushed_before: None,
+            users: Vec::new(),
+            org: None,
+            repo: None,
+            fork: None,
+            archived: None,
+            template: None,
+            sponsorable: false,
+            visibility: None,
+            mirror: None,
+            state: None,
+            labels: Vec::new(),
+            licenses: Vec::new(),
+            search_in: Vec::new(),
+            excluded_terms: Vec::new(),
+            excluded_languages: Vec::new(),
+            qualifiers: Vec::new(),
         }
     }
 
-    // Add a language filter to the search query
+    // Set or replace the search term after construction, e.g. when reusing
+    // one base query with a different term per iteration
+    pub fn term(mut self, term: &str) -> Self {
+        self.term = term.to_string();
+        self
+    }
+
+    // Fork a configured base query under a new term without consuming it,
+    // so one set of filters can back many per-term searches
+    pub fn with_term(&self, term: &str) -> Self {
+        self.clone().term(term)
+    }
+
+    // Require a further term; GitHub treats space-separated terms as AND
+    pub fn add_term(mut self, term: &str) -> Self {
+        self.extra_terms.push(term.to_string());
+        self
+    }
+
+    // Match any one of the given terms, joined with OR
+    pub fn or_terms(mut self, terms: &[&str]) -> Self {
+        self.any_terms.extend(terms.iter().map(|term| term.to_string()));
+        self
+    }
+
+    // Add a language filter to the search query; GitHub treats repeated
+    // `language:` qualifiers as an OR, so calling this again widens the search
     pub fn language(mut self, lang: &str) -> Self {
-        self.language = Some(lang.to_owned());
+        self.languages.push(lang.to_owned());
+        self
+    }
+
+    // Add several languages at once, matching repos in any of them
+    pub fn languages(mut self, langs: &[&str]) -> Self {
+        self.languages.extend(langs.iter().map(|lang| lang.to_string()));
         self
     }
 
     // Add a min_stars filter to the search query
-    pub fn min_stars(mut self, stars: &str) -> Self {
-        self.min_stars = Some(stars.to_owned());
+    pub fn min_stars(mut self, stars: u32) -> Self {
+        self.min_stars = Some(stars);
+        self
+    }
+
+    // Add a max_stars filter to the search query
+    pub fn max_stars(mut self, stars: u32) -> Self {
+        self.max_stars = Some(stars);
+        self
+    }
+
+    // Bound the star count on both ends, emitting `stars:min..max`
+    pub fn stars_range(self, min: u32, max: u32) -> Self {
+        self.min_stars(min).max_stars(max)
+    }
+
+    // String-based variant of `min_stars` that rejects non-numeric input
+    // instead of sending a malformed query to GitHub
+    pub fn try_min_stars(self, stars: &str) -> Result<Self, crate::errors::Error> {
+        let stars = stars.parse::<u32>().map_err(|_| {
+            crate::errors::Error::Other(format!("min_stars must be numeric, got '{}'", stars))
+        })?;
+        Ok(self.min_stars(stars))
+    }
+
+    // Require at least this many forks, emitting `forks:>=N`
+    pub fn min_forks(mut self, forks: &str) -> Self {
+        self.min_forks = Some(forks.to_owned());
+        self
+    }
+
+    // Bound the fork count on both ends, emitting `forks:min..max`
+    pub fn forks_range(mut self, min: &str, max: &str) -> Self {
+        self.min_forks = Some(min.to_owned());
+        self.max_forks = Some(max.to_owned());
+        self
+    }
+
+    // Bound the repository size in KB, emitting `size:min..max`
+    pub fn size_range(mut self, min: &str, max: &str) -> Self {
+        self.min_size = Some(min.to_owned());
+        self.max_size = Some(max.to_owned());
+        self
+    }
+
+    // Require more than `count` good-first-issues, emitting `good-first-issues:>N`
+    pub fn min_good_first_issues(mut self, count: u32) -> Self {
+        self.min_good_first_issues = Some(count);
+        self
+    }
+
+    // Require more than `count` help-wanted issues, emitting `help-wanted-issues:>N`
+    pub fn min_help_wanted_issues(mut self, count: u32) -> Self {
+        self.min_help_wanted_issues = Some(count);
         self
     }
 
-    // Add a topic filter to the search query
+    // Require a topic; calling this repeatedly requires every listed topic
     pub fn topic(mut self, topic: &str) -> Self {
-        self.topic = Some(topic.to_owned());
+        self.topics.push(topic.to_owned());
+        self
+    }
+
+    // Require all of the given topics at once
+    pub fn topics(mut self, topics: &[&str]) -> Self {
+        self.topics.extend(topics.iter().map(|topic| topic.to_string()));
+        self
+    }
+
+    // Only keep repositories created on or after the given date (YYYY-MM-DD)
+    pub fn created_after(mut self, date: &str) -> Self {
+        if is_valid_date(date) {
+            self.created_after = Some(date.to_owned());
+        }
+        self
+    }
+
+    // Only keep repositories created before the given date (YYYY-MM-DD)
+    pub fn created_before(mut self, date: &str) -> Self {
+        if is_valid_date(date) {
+            self.created_before = Some(date.to_owned());
+        }
         self
     }
 
+    // Only keep repositories created within the given date window (YYYY-MM-DD)
+    pub fn created_between(self, from: &str, to: &str) -> Self {
+        self.created_after(from).created_before(to)
+    }
+
+    // Only keep repositories pushed to on or after the given date (YYYY-MM-DD)
+    pub fn pushed_after(mut self, date: &str) -> Self {
+        if is_valid_date(date) {
+            self.pushed_after = Some(date.to_owned());
+        }
+        self
+    }
+
+    // Only keep repositories pushed to before the given date (YYYY-MM-DD)
+    pub fn pushed_before(mut self, date: &str) -> Self {
+        if is_valid_date(date) {
+            self.pushed_before = Some(date.to_owned());
+        }
+        self
+    }
+
+    // Exclude results matching a term, emitting `-term`
+    pub fn exclude_term(mut self, term: &str) -> Self {
+        self.excluded_terms.push(term.to_owned());
+        self
+    }
+
+    // Exclude a language from the results, emitting `-language:go`
+    pub fn exclude_language(mut self, lang: &str) -> Self {
+        self.excluded_languages.push(lang.to_owned());
+        self
+    }
+
+    // Limit results to a user's repositories; can be called repeatedly
+    pub fn user(mut self, user: &str) -> Self {
+        self.users.push(user.to_owned());
+        self
+    }
+
+    // Limit results to an organization's repositories
+    pub fn org(mut self, org: &str) -> Self {
+        self.org = Some(org.to_owned());
+        self
+    }
+
+    // Scope the search to a single repository, e.g. `rust-lang/rust`
+    pub fn repo(mut self, repo: &str) -> Self {
+        self.repo = Some(repo.to_owned());
+        self
+    }
+
+    // Drop forks from the results, emitting `fork:false`
+    pub fn exclude_forks(mut self) -> Self {
+        self.fork = Some("false".to_owned());
+        self
+    }
+
+    // Match only forks, emitting `fork:only`
+    pub fn only_forks(mut self) -> Self {
+        self.fork = Some("only".to_owned());
+        self
+    }
+
+    // Only match repositories with the given visibility; mainly useful on
+    // Enterprise, where private and internal repos dominate
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.visibility = Some(visibility);
+        self
+    }
+
+    // Drop mirrors from the results, emitting `mirror:false`
+    pub fn exclude_mirrors(mut self) -> Self {
+        self.mirror = Some(false);
+        self
+    }
+
+    // Drop archived repositories from the results, emitting `archived:false`
+    pub fn exclude_archived(mut self) -> Self {
+        self.archived = Some(false);
+        self
+    }
+
+    // Only match template repositories, emitting `template:true`
+    pub fn only_templates(mut self) -> Self {
+        self.template = Some(true);
+        self
+    }
+
+    // Only match repositories whose owner accepts sponsorships; GitHub only
+    // honors `is:sponsorable` on authenticated requests
+    pub fn is_sponsorable(mut self) -> Self {
+        self.sponsorable = true;
+        self
+    }
+
+    // Filter by license using an SPDX key, e.g. `mit` or `apache-2.0`;
+    // call repeatedly to OR several licenses together
+    pub fn license(mut self, license: &str) -> Self {
+        self.licenses.push(license.to_lowercase());
+        self
+    }
+
+    // Restrict where the search term matches, e.g. `in:name,description`
+    pub fn search_in(mut self, fields: &[SearchField]) -> Self {
+        self.search_in = fields.to_vec();
+        self
+    }
+
+    // Only match open issues (issue search)
+    pub fn is_open(mut self) -> Self {
+        self.state = Some("open".to_owned());
+        self
+    }
+
+    // Only match closed issues (issue search)
+    pub fn is_closed(mut self) -> Self {
+        self.state = Some("closed".to_owned());
+        self
+    }
+
+    // Require a label on matching issues; can be called repeatedly (issue search)
+    pub fn label(mut self, label: &str) -> Self {
+        self.labels.push(label.to_owned());
+        self
+    }
+
+    // Attach an arbitrary `key:op value` qualifier not covered by a dedicated
+    // method, so new GitHub qualifiers work without a builder change; values
+    // containing spaces are quoted when rendered
+    pub fn qualifier(mut self, key: &str, comparison: Comparison, value: &str) -> Self {
+        self.qualifiers.push((key.to_owned(), comparison, value.to_owned()));
+        self
+    }
+
+    // Require at least this many followers on the owning user or organization
+    pub fn min_followers(self, count: u32) -> Self {
+        self.qualifier("followers", Comparison::Gte, &count.to_string())
+    }
+
+    // Precise star comparators, for callers that need `stars:>N`, `stars:<N`
+    // or an exact count rather than the merged ranges `min_stars`/`max_stars`
+    // produce. Each emits exactly the comparator GitHub documents.
+    pub fn stars_gt(self, count: u32) -> Self {
+        self.qualifier("stars", Comparison::Gt, &count.to_string())
+    }
+
+    pub fn stars_gte(self, count: u32) -> Self {
+        self.qualifier("stars", Comparison::Gte, &count.to_string())
+    }
+
+    pub fn stars_lt(self, count: u32) -> Self {
+        self.qualifier("stars", Comparison::Lt, &count.to_string())
+    }
+
+    pub fn stars_lte(self, count: u32) -> Self {
+        self.qualifier("stars", Comparison::Lte, &count.to_string())
+    }
+
+    pub fn stars_exact(self, count: u32) -> Self {
+        self.qualifier("stars", Comparison::Eq, &count.to_string())
+    }
+
+    // Enumerate the qualifiers currently set, as (name, value) pairs in
+    // rendering order, so a UI can reflect the query state without parsing
+    // the rendered string. Search terms are not included.
+    pub fn active_qualifiers(&self) -> Vec<(&str, String)> {
+        let mut active: Vec<(&str, String)> = Vec::new();
+        if !self.search_in.is_empty() {
+            let fields: Vec<&str> = self.search_in.iter().map(SearchField::as_str).collect();
+            active.push(("in", fields.join(",")));
+        }
+        for language in &self.languages {
+            active.push(("language", language.clone()));
+        }
+        // The same merged ranges the rendered query uses
+        match (&self.min_stars, &self.max_stars) {
+            (Some(min), Some(max)) => active.push(("stars", format!("{}..{}", min, max))),
+            (Some(min), None) => active.push(("stars", format!(">={}", min))),
+            (None, Some(max)) => active.push(("stars", format!("<={}", max))),
+            (None, None) => {}
+        }
+        match (&self.min_forks, &self.max_forks) {
+            (Some(min), Some(max)) => active.push(("forks", format!("{}..{}", min, max))),
+            (Some(min), None) => active.push(("forks", format!(">={}", min))),
+            (None, Some(max)) => active.push(("forks", format!("<={}", max))),
+            (None, None) => {}
+        }
+        match (&self.min_size, &self.max_size) {
+            (Some(min), Some(max)) => active.push(("size", format!("{}..{}", min, max))),
+            (Some(min), None) => active.push(("size", format!(">={}", min))),
+            (None, Some(max)) => active.push(("size", format!("<={}", max))),
+            (None, None) => {}
+        }
+        if let Some(count) = &self.min_good_first_issues {
+            active.push(("good-first-issues", format!(">{}", count)));
+        }
+        if let Some(count) = &self.min_help_wanted_issues {
+            active.push(("help-wanted-issues", format!(">{}", count)));
+        }
+        for topic in &self.topics {
+            active.push(("topic", topic.clone()));
+        }
+        match (&self.created_after, &self.created_before) {
+            (Some(from), Some(to)) => active.push(("created", format!("{}..{}", from, to))),
+            (Some(from), None) => active.push(("created", format!(">={}", from))),
+            (None, Some(to)) => active.push(("created", format!("<{}", to))),
+            (None, None) => {}
+        }
+        match (&self.pushed_after, &self.pushed_before) {
+            (Some(from), Some(to)) => active.push(("pushed", format!("{}..{}", from, to))),
+            (Some(from), None) => active.push(("pushed", format!(">={}", from))),
+            (None, Some(to)) => active.push(("pushed", format!("<{}", to))),
+            (None, None) => {}
+        }
+        for user in &self.users {
+            active.push(("user", user.clone()));
+        }
+        if let Some(org) = &self.org {
+            active.push(("org", org.clone()));
+        }
+        if let Some(repo) = &self.repo {
+            active.push(("repo", repo.clone()));
+        }
+        if let Some(fork) = &self.fork {
+            active.push(("fork", fork.clone()));
+        }
+        if let Some(archived) = &self.archived {
+            active.push(("archived", archived.to_string()));
+        }
+        if let Some(template) = &self.template {
+            active.push(("template", template.to_string()));
+        }
+        if self.sponsorable {
+            active.push(("is", "sponsorable".to_owned()));
+        }
+        if let Some(visibility) = &self.visibility {
+            active.push(("is", visibility.as_str().to_owned()));
+        }
+        if let Some(mirror) = &self.mirror {
+            active.push(("mirror", mirror.to_string()));
+        }
+        if let Some(state) = &self.state {
+            active.push(("is", state.clone()));
+        }
+        for label in &self.labels {
+            active.push(("label", label.clone()));
+        }
+        for license in &self.licenses {
+            active.push(("license", license.clone()));
+        }
+        for (key, comparison, value) in &self.qualifiers {
+            active.push((key.as_str(), format!("{}{}", comparison.as_str(), value)));
+        }
+        for lang in &self.excluded_languages {
+            active.push(("-language", lang.clone()));
+        }
+        active
+    }
+
+    // True when no qualifiers are set, i.e. the query is only its term(s)
+    pub fn is_empty(&self) -> bool {
+        self.active_qualifiers().is_empty() && self.excluded_terms.is_empty()
+    }
+
+    // Check the rendered query against GitHub's length and operator limits
+    // without sending it; the search functions also run this automatically
+    pub fn validate(&self) -> Result<(), crate::errors::Error> {
+        validate_query(&self.to_query_string())
+    }
+
     // Convert the query to a GitHub-compatible query string
     pub fn to_query_string(&self) -> String {
-        let mut query = self.term.clone();
-        if let Some(language) = &self.language {
+        let mut query = quote_term(&self.term);
+        for term in &self.extra_terms {
+            query.push_str(&format!(" {}", quote_term(term)));
+        }
+        if !self.any_terms.is_empty() {
+            // GitHub's OR binds the listed terms into one alternative group
+            let quoted: Vec<String> = self.any_terms.iter().map(|term| quote_term(term)).collect();
+            query.push_str(&format!(" {}", quoted.join(" OR ")));
+        }
+        if !self.search_in.is_empty() {
+            let fields: Vec<&str> = self.search_in.iter().map(SearchField::as_str).collect();
+            query.push_str(&format!(" in:{}", fields.join(",")));
+        }
+        for language in &self.languages {
+            query.push_str(&format!(" language:{}", language));
+        }
+        // Merge both bounds into a single range qualifier when they are set together
+        match (&self.min_stars, &self.max_stars) {
+            (Some(min), Some(max)) => query.push_str(&format!(" stars:{}..{}", min, max)),
+            (Some(min), None) => query.push_str(&format!(" stars:>={}", min)),
+            (None, Some(max)) => query.push_str(&format!(" stars:<={}", max)),
+            (None, None) => {}
+        }
+        match (&self.min_forks, &self.max_forks) {
+            (Some(min), Some(max)) => query.push_str(&format!(" forks:{}..{}", min, max)),
+            (Some(min), None) => query.push_str(&format!(" forks:>={}", min)),
+            (None, Some(max)) => query.push_str(&format!(" forks:<={}", max)),
+            (None, None) => {}
+        }
+        match (&self.min_size, &self.max_size) {
+            (Some(min), Some(max)) => query.push_str(&format!(" size:{}..{}", min, max)),
+            (Some(min), None) => query.push_str(&format!(" size:>={}", min)),
+            (None, Some(max)) => query.push_str(&format!(" size:<={}", max)),
+            (None, None) => {}
+        }
+        if let Some(count) = &self.min_good_first_issues {
+            query.push_str(&format!(" good-first-issues:>{}", count));
+        }
+        if let Some(count) = &self.min_help_wanted_issues {
+            query.push_str(&format!(" help-wanted-issues:>{}", count));
+        }
+        // Each topic is a separate qualifier; GitHub ANDs them together
+        for topic in &self.topics {
+            query.push_str(&format!(" topic:{}", topic));
+        }
+        // Merge both bounds into a single range qualifier when they are set together
+        match (&self.created_after, &self.created_before) {
+            (Some(from), Some(to)) => query.push_str(&format!(" created:{}..{}", from, to)),
+            (Some(from), None) => query.push_str(&format!(" created:>={}", from)),
+            (None, Some(to)) => query.push_str(&format!(" created:<{}", to)),
+            (None, None) => {}
+        }
+        match (&self.pushed_after, &self.pushed_before) {
+            (Some(from), Some(to)) => query.push_str(&format!(" pushed:{}..{}", from, to)),
+            (Some(from), None) => query.push_str(&format!(" pushed:>={}", from)),
+            (None, Some(to)) => query.push_str(&format!(" pushed:<{}", to)),
+            (None, None) => {}
+        }
+        for user in &self.users {
+            query.push_str(&format!(" user:{}", user));
+        }
+        if let Some(org) = &self.org {
+            query.push_str(&format!(" org:{}", org));
+        }
+        if let Some(repo) = &self.repo {
+            query.push_str(&format!(" repo:{}", repo));
+        }
+        if let Some(fork) = &self.fork {
+            query.push_str(&format!(" fork:{}", fork));
+        }
+        if let Some(archived) = &self.archived {
+            query.push_str(&format!(" archived:{}", archived));
+        }
+        if let Some(template) = &self.template {
+            query.push_str(&format!(" template:{}", template));
+        }
+        if self.sponsorable {
+            query.push_str(" is:sponsorable");
+        }
+        if let Some(visibility) = &self.visibility {
+            query.push_str(&format!(" is:{}", visibility.as_str()));
+        }
+        if let Some(mirror) = &self.mirror {
+            query.push_str(&format!(" mirror:{}", mirror));
+        }
+        if let Some(state) = &self.state {
+            query.push_str(&format!(" is:{}", state));
+        }
+        for label in &self.labels {
+            query.push_str(&format!(" label:{}", label));
+        }
+        for license in &self.licenses {
+            query.push_str(&format!(" license:{}", license));
+        }
+        for (key, comparison, value) in &self.qualifiers {
+            // Quote values with spaces so they stay one qualifier
+            if value.contains(' ') {
+                query.push_str(&format!(" {}:{}\"{}\"", key, comparison.as_str(), value));
+            } else {
+                query.push_str(&format!(" {}:{}{}", key, comparison.as_str(), value));
+            }
+        }
+        for term in &self.excluded_terms {
+            query.push_str(&format!(" -{}", term));
+        }
+        for lang in &self.excluded_languages {
+            query.push_str(&format!(" -language:{}", lang));
+        }
+        query
+    }
+}
+// An empty query to build on incrementally; set a term before searching
+impl Default for GithubSearchQuery {
+    fn default() -> Self {
+        Self::new("")
+    }
+}
+
+impl From<&str> for GithubSearchQuery {
+    fn from(term: &str) -> Self {
+        Self::new(term)
+    }
+}
+
+impl From<String> for GithubSearchQuery {
+    fn from(term: String) -> Self {
+        Self::new(&term)
+    }
+}
+
+// Printing a query shows the string that would be sent to GitHub
+impl std::fmt::Display for GithubSearchQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_query_string())
+    }
+}
+
+// A builder for code-search queries, mirroring `GithubSearchQuery` but
+// emitting the qualifiers the `/search/code` endpoint understands
+#[derive(Clone, Debug, PartialEq)]
+pub struct CodeSearchQuery {
+    pub term: String,
+    pub paths: Vec<String>,      // `path:` qualifiers, one per entry
+    pub extensions: Vec<String>, // `extension:` qualifiers, one per entry
+    pub languages: Vec<String>,  // `language:` qualifiers, one per entry
+    pub repo: Option<String>,    // Restrict the search to one repository
+    pub filename: Option<String>, // Only match files with this name
+    // Request-level options carried alongside the query so callers don't
+    // thread them through the search call as positional arguments
+    pub highlight: bool,         // Ask for text-match fragments
+    pub sort: Option<String>,    // Only `indexed` is supported by GitHub
+    pub order: Option<String>,   // `asc` or `desc`
+}
+
+impl CodeSearchQuery {
+    pub fn new(term: &str) -> Self {
+        CodeSearchQuery {
+            term: term.to_string(),
+            paths: Vec::new(),
+            extensions: Vec::new(),
+            languages: Vec::new(),
+            repo: None,
+            filename: None,
+            highlight: false,
+            sort: None,
+            order: None,
+        }
+    }
+
+    // Only match files under this directory, e.g. `src/` or `tests`
+    pub fn path(mut self, path: &str) -> Self {
+        self.paths.push(path.to_string());
+        self
+    }
+
+    // Only match files with this extension, without the leading dot
+    pub fn extension(mut self, extension: &str) -> Self {
+        self.extensions.push(extension.trim_start_matches('.').to_string());
+        self
+    }
+
+    pub fn language(mut self, language: &str) -> Self {
+        self.languages.push(language.to_string());
+        self
+    }
+
+    // Restrict the search to a single repository, given as `owner/name`
+    pub fn in_repo(mut self, repo: &str) -> Self {
+        self.repo = Some(repo.to_string());
+        self
+    }
+
+    // Only match files with this exact name, e.g. `Cargo.toml`
+    pub fn filename(mut self, filename: &str) -> Self {
+        self.filename = Some(filename.to_string());
+        self
+    }
+
+    // Request text-match fragments for a grep-like preview of each hit
+    pub fn highlight(mut self) -> Self {
+        self.highlight = true;
+        self
+    }
+
+    // Order hits by when GitHub indexed them instead of by best match
+    pub fn sort_by_indexed(mut self) -> Self {
+        self.sort = Some("indexed".to_owned());
+        self
+    }
+
+    // `asc` or `desc`; only meaningful together with `sort_by_indexed`
+    pub fn order(mut self, order: &str) -> Self {
+        self.order = Some(order.to_owned());
+        self
+    }
+
+    // Assemble the final query string for the code-search endpoint
+    pub fn to_query_string(&self) -> String {
+        let mut query = quote_term(&self.term);
+        for path in &self.paths {
+            query.push_str(&format!(" path:{}", path));
+        }
+        for extension in &self.extensions {
+            query.push_str(&format!(" extension:{}", extension));
+        }
+        for language in &self.languages {
             query.push_str(&format!(" language:{}", language));
         }
-        if let Some(stars) = &self.min_stars {
-            query.push_str(&format!(" stars:>={}", stars));
+        if let Some(repo) = &self.repo {
+            query.push_str(&format!(" repo:{}", repo));
         }
-        if let Some(topic) = &self.topic {
-            query.push_str(&format!(" (topic:{})", topic));
+        if let Some(filename) = &self.filename {
+            query.push_str(&format!(" filename:{}", filename));
         }
         query
     }
-}
\ No newline at end of file
+}
+
+impl std::fmt::Display for CodeSearchQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_query_string())
+    }
+}
+
+// Whether a user search should match people, organizations, or both
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UserType {
+    User,
+    Org,
+}
+
+impl UserType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UserType::User => "user",
+            UserType::Org => "org",
+        }
+    }
+}
+
+// A builder for user-search queries against `/search/users`
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserSearchQuery {
+    pub term: String,
+    pub min_followers: Option<u32>, // `followers:>=N`
+    pub min_repos: Option<u32>,     // `repos:>=N`
+    pub user_type: Option<UserType>, // `type:user` or `type:org`
+    pub location: Option<String>,   // `location:` qualifier
+}
+
+impl UserSearchQuery {
+    pub fn new(term: &str) -> Self {
+        UserSearchQuery {
+            term: term.to_string(),
+            min_followers: None,
+            min_repos: None,
+            user_type: None,
+            location: None,
+        }
+    }
+
+    // Only match accounts with at least this many followers
+    pub fn min_followers(mut self, count: u32) -> Self {
+        self.min_followers = Some(count);
+        self
+    }
+
+    // Only match accounts owning at least this many public repositories
+    pub fn min_repos(mut self, count: u32) -> Self {
+        self.min_repos = Some(count);
+        self
+    }
+
+    // Restrict matches to people or to organizations
+    pub fn user_type(mut self, user_type: UserType) -> Self {
+        self.user_type = Some(user_type);
+        self
+    }
+
+    pub fn location(mut self, location: &str) -> Self {
+        self.location = Some(location.to_string());
+        self
+    }
+
+    // Assemble the final query string for the user-search endpoint
+    pub fn to_query_string(&self) -> String {
+        let mut query = quote_term(&self.term);
+        if let Some(followers) = self.min_followers {
+            query.push_str(&format!(" followers:>={}", followers));
+        }
+        if let Some(repos) = self.min_repos {
+            query.push_str(&format!(" repos:>={}", repos));
+        }
+        if let Some(user_type) = self.user_type {
+            query.push_str(&format!(" type:{}", user_type.as_str()));
+        }
+        if let Some(location) = &self.location {
+            query.push_str(&format!(" location:{}", location));
+        }
+        query
+    }
+}
+
+impl std::fmt::Display for UserSearchQuery {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_query_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn min_stars_only() {
+        let query = GithubSearchQuery::new("rust").min_stars(100).to_query_string();
+        assert_eq!(query, "rust stars:>=100");
+    }
+
+    #[test]
+    fn max_stars_only() {
+        let query = GithubSearchQuery::new("rust").max_stars(1000).to_query_string();
+        assert_eq!(query, "rust stars:<=1000");
+    }
+
+    #[test]
+    fn min_and_max_stars_merge_into_a_range() {
+        let query = GithubSearchQuery::new("rust")
+            .min_stars(100)
+            .max_stars(1000)
+            .to_query_string();
+        assert_eq!(query, "rust stars:100..1000");
+    }
+
+    #[test]
+    fn multiple_languages_emit_one_qualifier_each() {
+        let query = GithubSearchQuery::new("web")
+            .languages(&["rust", "go", "zig"])
+            .to_query_string();
+        assert_eq!(query, "web language:rust language:go language:zig");
+    }
+
+    #[test]
+    fn beginner_friendly_issue_filters() {
+        let query = GithubSearchQuery::new("rust")
+            .min_good_first_issues(5)
+            .min_help_wanted_issues(2)
+            .to_query_string();
+        assert_eq!(query, "rust good-first-issues:>5 help-wanted-issues:>2");
+    }
+
+    #[test]
+    fn try_min_stars_rejects_non_numeric_input() {
+        assert!(GithubSearchQuery::new("rust").try_min_stars("abc").is_err());
+        assert!(GithubSearchQuery::new("rust").try_min_stars("100").is_ok());
+    }
+
+    #[test]
+    fn exclusions_space_join_after_the_term() {
+        let query = GithubSearchQuery::new("rust")
+            .exclude_term("windows")
+            .exclude_language("go")
+            .to_query_string();
+        assert_eq!(query, "rust -windows -language:go");
+    }
+
+    #[test]
+    fn min_forks_only() {
+        let query = GithubSearchQuery::new("rust").min_forks("50").to_query_string();
+        assert_eq!(query, "rust forks:>=50");
+    }
+
+    #[test]
+    fn forks_and_size_ranges() {
+        let query = GithubSearchQuery::new("rust")
+            .forks_range("10", "100")
+            .size_range("500", "10000")
+            .to_query_string();
+        assert_eq!(query, "rust forks:10..100 size:500..10000");
+    }
+
+    #[test]
+    fn multi_word_terms_are_quoted_as_a_phrase() {
+        let query = GithubSearchQuery::new("rust async").language("rust").to_query_string();
+        assert_eq!(query, "\"rust async\" language:rust");
+    }
+
+    #[test]
+    fn exclude_forks_combines_with_min_stars() {
+        let query = GithubSearchQuery::new("rust")
+            .min_stars(100)
+            .exclude_forks()
+            .exclude_archived()
+            .to_query_string();
+        assert_eq!(query, "rust stars:>=100 fork:false archived:false");
+    }
+
+    #[test]
+    fn license_is_lowercased_and_repeatable() {
+        let query = GithubSearchQuery::new("rust")
+            .license("MIT")
+            .license("Apache-2.0")
+            .to_query_string();
+        assert_eq!(query, "rust license:mit license:apache-2.0");
+    }
+
+    #[test]
+    fn explicit_stars_range() {
+        let query = GithubSearchQuery::new("rust").stars_range(100, 1000).to_query_string();
+        assert_eq!(query, "rust stars:100..1000");
+    }
+
+    #[test]
+    fn code_query_with_path_and_extension() {
+        let query = CodeSearchQuery::new("tokio::spawn")
+            .path("src")
+            .extension("rs")
+            .to_query_string();
+        assert_eq!(query, "tokio::spawn path:src extension:rs");
+    }
+
+    #[test]
+    fn code_query_scoped_to_a_repository() {
+        let query = CodeSearchQuery::new("unsafe")
+            .language("rust")
+            .in_repo("rust-lang/rust")
+            .filename("lib.rs")
+            .to_query_string();
+        assert_eq!(query, "unsafe language:rust repo:rust-lang/rust filename:lib.rs");
+    }
+
+    #[test]
+    fn user_query_with_follower_and_repo_thresholds() {
+        let query = UserSearchQuery::new("tom")
+            .min_followers(1000)
+            .min_repos(50)
+            .to_query_string();
+        assert_eq!(query, "tom followers:>=1000 repos:>=50");
+    }
+
+    #[test]
+    fn user_query_restricted_to_organizations() {
+        let query = UserSearchQuery::new("rust")
+            .user_type(UserType::Org)
+            .to_query_string();
+        assert_eq!(query, "rust type:org");
+    }
+
+    #[test]
+    fn added_terms_are_space_separated() {
+        let query = GithubSearchQuery::new("async")
+            .add_term("runtime")
+            .to_query_string();
+        assert_eq!(query, "async runtime");
+    }
+
+    #[test]
+    fn or_terms_join_with_or() {
+        let query = GithubSearchQuery::new("web")
+            .or_terms(&["tokio", "async-std"])
+            .to_query_string();
+        assert_eq!(query, "web tokio OR async-std");
+    }
+
+    #[test]
+    fn multi_word_added_terms_are_quoted() {
+        let query = GithubSearchQuery::new("parser")
+            .add_term("zero copy")
+            .to_query_string();
+        assert_eq!(query, "parser \"zero copy\"");
+    }
+
+    #[test]
+    fn multiple_topics_all_required() {
+        let query = GithubSearchQuery::new("ml")
+            .topic("machine-learning")
+            .topic("rust")
+            .to_query_string();
+        assert_eq!(query, "ml topic:machine-learning topic:rust");
+    }
+
+    #[test]
+    fn topics_helper_matches_repeated_topic_calls() {
+        let from_slice = GithubSearchQuery::new("ml").topics(&["machine-learning", "rust"]);
+        let from_calls = GithubSearchQuery::new("ml").topic("machine-learning").topic("rust");
+        assert_eq!(from_slice, from_calls);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn normalize_query_ignores_qualifier_order() {
+        let a = normalize_query("rust language:rust stars:>=100");
+        let b = normalize_query("rust stars:>=100 language:rust");
+        assert_eq!(a, b);
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn normalize_query_keeps_quoted_phrases_and_term_order() {
+        let normalized = normalize_query("\"web framework\" fast Language:rust");
+        assert_eq!(normalized, "\"web framework\" fast language:rust");
+    }
+
+    #[test]
+    fn active_qualifiers_reflect_the_configured_filters() {
+        let query = GithubSearchQuery::new("rust")
+            .language("rust")
+            .min_stars(100)
+            .max_stars(1000)
+            .topic("cli");
+        assert_eq!(
+            query.active_qualifiers(),
+            vec![
+                ("language", "rust".to_owned()),
+                ("stars", "100..1000".to_owned()),
+                ("topic", "cli".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn is_empty_is_true_for_a_bare_term() {
+        assert!(GithubSearchQuery::new("rust").is_empty());
+        assert!(!GithubSearchQuery::new("rust").min_stars(1).is_empty());
+    }
+
+    #[test]
+    fn custom_qualifiers_render_key_comparator_value() {
+        let query = GithubSearchQuery::new("rust")
+            .qualifier("followers", Comparison::Gt, "50")
+            .qualifier("created", Comparison::Lt, "2020-01-01")
+            .to_query_string();
+        assert_eq!(query, "rust followers:>50 created:<2020-01-01");
+    }
+
+    #[test]
+    fn custom_qualifier_values_with_spaces_are_quoted() {
+        let query = GithubSearchQuery::new("cli")
+            .qualifier("label", Comparison::Eq, "good first issue")
+            .to_query_string();
+        assert_eq!(query, "cli label:\"good first issue\"");
+    }
+
+    #[test]
+    fn star_comparators_emit_the_exact_operator() {
+        assert_eq!(GithubSearchQuery::new("a").stars_gt(10).to_query_string(), "a stars:>10");
+        assert_eq!(GithubSearchQuery::new("a").stars_gte(10).to_query_string(), "a stars:>=10");
+        assert_eq!(GithubSearchQuery::new("a").stars_lt(10).to_query_string(), "a stars:<10");
+        assert_eq!(GithubSearchQuery::new("a").stars_lte(10).to_query_string(), "a stars:<=10");
+        assert_eq!(GithubSearchQuery::new("a").stars_exact(10).to_query_string(), "a stars:10");
+    }
+
+    #[test]
+    fn min_followers_uses_the_qualifier_primitive() {
+        let query = GithubSearchQuery::new("rust").min_followers(100).to_query_string();
+        assert_eq!(query, "rust followers:>=100");
+    }
+
+    #[test]
+    fn with_term_forks_a_base_query_keeping_filters() {
+        let base = GithubSearchQuery::new("").language("rust").min_stars(100);
+        assert_eq!(base.with_term("web").to_query_string(), "web language:rust stars:>=100");
+        assert_eq!(base.with_term("cli").to_query_string(), "cli language:rust stars:>=100");
+    }
+
+    #[test]
+    fn validate_rejects_overlong_queries() {
+        let query = GithubSearchQuery::new(&"a".repeat(300));
+        assert!(query.validate().is_err());
+        assert!(GithubSearchQuery::new("rust").validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_too_many_operators() {
+        assert!(validate_query("a OR b OR c OR d OR e OR f OR g").is_err());
+        assert!(validate_query("a OR b NOT c AND d").is_ok());
+    }
+
+    #[test]
+    fn from_str_matches_new() {
+        assert_eq!(GithubSearchQuery::from("rust async"), GithubSearchQuery::new("rust async"));
+    }
+
+    #[test]
+    fn term_replaces_the_search_term() {
+        let base = GithubSearchQuery::default().language("rust");
+        let query = base.term("cli").to_query_string();
+        assert_eq!(query, "cli language:rust");
+    }
+
+    #[test]
+    fn sponsorable_template_repositories() {
+        let query = GithubSearchQuery::new("cli")
+            .only_templates()
+            .is_sponsorable()
+            .to_query_string();
+        assert_eq!(query, "cli template:true is:sponsorable");
+    }
+}
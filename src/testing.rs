@@ -0,0 +1,103 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use futures::future::BoxFuture;
+
+use crate::errors::Error;
+use crate::http_backend::{HttpBackend, HttpResponse};
+
+// An in-memory `HttpBackend` that serves queued responses in order, so code
+// built on `GithubClient` can be unit-tested without hitting the real API
+pub struct MockBackend {
+    responses: Mutex<VecDeque<HttpResponse>>,
+}
+
+impl MockBackend {
+    pub fn new() -> Self {
+        Self {
+            responses: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    // Queue a response; each request consumes the oldest queued one
+    pub fn push_response(&self, status: u16, body: &str) {
+        let response = HttpResponse {
+            status: reqwest::StatusCode::from_u16(status).expect("invalid status code"),
+            headers: reqwest::header::HeaderMap::new(),
+            body: bytes::Bytes::from(body.to_owned()),
+        };
+        self.responses.lock().unwrap().push_back(response);
+    }
+}
+
+impl Default for MockBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpBackend for MockBackend {
+    fn execute(&self, _request: reqwest::Request) -> BoxFuture<'_, Result<HttpResponse, Error>> {
+        Box::pin(async move {
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| Error::Other("MockBackend has no queued responses".to_string()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api_client::GithubClient;
+    use crate::cache::Cache;
+
+    #[tokio::test]
+    async fn search_goes_through_an_injected_backend() {
+        let backend = std::sync::Arc::new(MockBackend::new());
+        backend.push_response(
+            200,
+            r#"{"total_count":1,"incomplete_results":false,"items":[{"full_name":"rust-lang/rust","description":null,"stargazers_count":1,"language":"Rust","html_url":"https://github.com/rust-lang/rust"}]}"#,
+        );
+
+        let client = GithubClient::builder()
+            .http_backend(backend)
+            .build()
+            .unwrap();
+        let cache = Cache::new_unbounded();
+
+        let response = client
+            .search_repositories(&cache, "rust", None, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(response.total_count, 1);
+        assert_eq!(response.items[0].full_name, "rust-lang/rust");
+    }
+
+    #[tokio::test]
+    async fn repeated_bad_queries_replay_the_cached_422() {
+        let backend = std::sync::Arc::new(MockBackend::new());
+        backend.push_response(422, r#"{"message":"Validation Failed"}"#);
+
+        let client = GithubClient::builder()
+            .http_backend(backend)
+            .max_retries(0)
+            .build()
+            .unwrap();
+        let cache = Cache::new_unbounded();
+
+        let first = client
+            .search_repositories(&cache, "stars:banana", None, None, None, None)
+            .await;
+        assert!(matches!(first, Err(crate::errors::Error::ApiError { status, .. }) if status == 422));
+
+        // No more queued responses: a second submission must come from the
+        // negative cache rather than the backend
+        let second = client
+            .search_repositories(&cache, "stars:banana", None, None, None, None)
+            .await;
+        assert!(matches!(second, Err(crate::errors::Error::ApiError { status, .. }) if status == 422));
+    }
+}